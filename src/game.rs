@@ -0,0 +1,704 @@
+// The terminal game: the title screen, playing levels one after another and the
+// endless and daily modes. Everything is drawn by `Drawing` to any writer.
+use crossterm::{
+    event::Event, event::KeyCode, event::KeyEvent, event::KeyModifiers, event::MouseButton,
+    event::MouseEvent, event::MouseEventKind, terminal::disable_raw_mode, Result,
+};
+
+use crate::utils::clock::Clock;
+use crate::utils::config::Config;
+use crate::utils::config::CONFIG_FILE;
+use crate::utils::daily;
+use crate::utils::daily::DailyHistory;
+use crate::utils::daily::Date;
+use crate::utils::daily::DAILY_FILE;
+use crate::utils::death::Death;
+use crate::utils::direction::Direction;
+use crate::utils::drawing::hint_text;
+use crate::utils::drawing::intro_lines;
+use crate::utils::drawing::Drawing;
+use crate::utils::drawing::DEATH_ANIMATION;
+use crate::utils::game_state::Command;
+use crate::utils::game_state::GameState;
+use crate::utils::game_state::Mode;
+use crate::utils::generator::generate_retrying;
+use crate::utils::generator::GeneratorParams;
+use crate::utils::input::InputSource;
+use crate::utils::level::neighbour;
+use crate::utils::level::Level;
+use crate::utils::menu::Menu;
+use crate::utils::menu::MenuAction;
+use crate::utils::menu::Screen;
+use crate::utils::narration;
+use crate::utils::point::Point;
+use crate::utils::progress::Progress;
+use crate::utils::progress::SAVE_FILE;
+use crate::utils::snapshot::snapshot;
+use crate::utils::snapshot::SnapshotFormat;
+use crate::utils::snapshot::SNAPSHOT_FILE;
+use crate::utils::solver;
+use crate::utils::solver::Hint;
+use crate::utils::solver::Solution;
+use crate::utils::solver::HINT_STATES;
+use crate::utils::solver::MAX_STATES;
+use crate::utils::stats::export;
+use crate::utils::stats::ExportFormat;
+use crate::utils::stats::LevelStats;
+use std::collections::VecDeque;
+use std::io::Error;
+use std::io::Write;
+use std::panic::Location;
+use std::time::Duration;
+
+// The arrow key moving the player in the direction, to walk a path.
+fn arrow_key(direction: Direction) -> KeyCode {
+    match direction {
+        Direction::Up => KeyCode::Up,
+        Direction::Down => KeyCode::Down,
+        Direction::Left => KeyCode::Left,
+        Direction::Right => KeyCode::Right,
+    }
+}
+
+// The state after a transition the game only asks for where it is allowed, like completing
+// a level while playing. Any other is a bug, it ends the run with an error saying where.
+#[track_caller]
+fn allowed(game_state: GameState, next: Option<GameState>) -> Result<GameState> {
+    let caller = Location::caller();
+    next.ok_or_else(|| {
+        Error::other(format!(
+            "transition not allowed from {:?} at {}",
+            game_state.mode(),
+            caller
+        ))
+    })
+}
+
+// Play the death animation at the point and take a life, when we are counting them.
+// The accessible mode only says what happened.
+fn die(
+    drawing: &mut Drawing<impl Write>,
+    clock: &impl Clock,
+    game_state: GameState,
+    death: Death,
+    point: Point,
+    max_y: i8,
+    accessible: bool,
+) -> Result<GameState> {
+    let game_state = allowed(game_state, game_state.die(death))?;
+    if accessible {
+        drawing.narrate(&[String::from(death.message())])?;
+    } else {
+        for frame in 0..DEATH_ANIMATION.len() {
+            drawing.draw_death(point, death, frame, max_y as u16)?;
+            drawing.flush()?;
+            clock.sleep(Duration::from_millis(150));
+        }
+    }
+    let game_state = allowed(game_state, game_state.lose_life())?;
+    if game_state.is_game_over() {
+        if accessible {
+            drawing.narrate(&[String::from("Game over! No lives left.")])?;
+        } else {
+            drawing.draw_game_over(max_y as u16)?;
+        }
+        drawing.flush()?;
+        clock.sleep(Duration::from_secs(2));
+    }
+    Ok(game_state)
+}
+
+/// Play the given levels until the last one is complete, the player quits or the game is over.
+/// `first_level` is the index of the first one in all levels. Level times and the enemies
+/// follow the clock. The accessible mode describes the level in text instead of drawing it.
+/// Returns the statistics of every level played, the one the run ended on marked unfinished.
+pub fn game_loop(
+    drawing: &mut Drawing<impl Write>,
+    input: &mut impl InputSource,
+    clock: &impl Clock,
+    levels: impl IntoIterator<Item = Level>,
+    first_level: usize,
+    progress: &mut Progress,
+    config: &Config,
+) -> Result<Vec<LevelStats>> {
+    let accessible = config.accessible;
+    let game_state = GameState::new().new_lives(config.lives);
+    let mut game_state = allowed(game_state, game_state.start())?;
+    // Store the statistics for each level to later show them to the player
+    let mut stats: Vec<LevelStats> = vec![];
+
+    // for each level, clone the level into the buffer for modification.
+    // Than run the game loop
+    for (level_index, level) in levels.into_iter().enumerate() {
+        let level_index = first_level + level_index;
+        // the first level is already playing, the others start once the one before is complete
+        match game_state.mode() {
+            Mode::Playing => {}
+            Mode::LevelComplete => game_state = allowed(game_state, game_state.start())?,
+            _ => break,
+        }
+        let mut cloned_level = level.clone();
+        // where the player is, or was last seen before dying
+        let mut player = cloned_level
+            .player_position()
+            .unwrap_or(Point { x: 0, y: 0 });
+        let (max_x, max_y) = level.size();
+        let info = level.info();
+        let mut level_stats = LevelStats {
+            par_time: info.par_time,
+            par_moves: info.par_moves,
+            ..LevelStats::new(level_index + 1)
+        };
+        if !info.intro.is_empty() {
+            if accessible {
+                drawing.narrate(&intro_lines(level_index + 1, info))?;
+            } else {
+                drawing.draw_intro(level_index + 1, info)?;
+            }
+            drawing.flush()?;
+            // wait for a key, quitting goes back to the title screen
+            loop {
+                if let Event::Key(event) = input.read_event()? {
+                    if let KeyCode::Esc | KeyCode::Char('q') = event.code {
+                        game_state = allowed(game_state, game_state.quit())?;
+                    }
+                    break;
+                }
+            }
+            if !game_state.is_playing() {
+                break;
+            }
+        }
+        // Count time needed
+        let mut level_start = clock.now();
+        let enemy_move_interval = Duration::from_millis(info.enemy_interval);
+        let mut last_enemy_move = clock.now();
+        // set while the game is paused, the timers continue from here
+        let mut paused_at: Option<Duration> = None;
+        // shown until the player moves on
+        let mut hint: Option<Hint> = None;
+        // where the last snapshot was saved, shown until the player moves on
+        let mut notice: Option<String> = None;
+        // moves left of a mouse click, one is taken every tick
+        let mut path: VecDeque<Direction> = VecDeque::new();
+        // the clicked cell the path leads to
+        let mut path_target: Option<Point> = None;
+        // the accessible mode only says what changed since the last frame
+        let mut narrated: Option<(narration::Snapshot, String, bool, bool)> = None;
+        // set after dying or pressing "r", the level starts over on the next frame
+        let mut restart = false;
+        while game_state.is_playing() || game_state.is_paused() {
+            if restart {
+                restart = false;
+                cloned_level = level.clone();
+                player = cloned_level.player_position().unwrap_or(player);
+                // moves and time are those of the attempt reaching the exit
+                level_stats.moves = 0;
+                level_start = clock.now();
+                last_enemy_move = clock.now();
+                paused_at = None;
+                hint = None;
+                notice = None;
+                narrated = None;
+                path.clear();
+            }
+
+            let elapsed = match paused_at {
+                Some(paused_at) => paused_at - level_start,
+                None => clock.now() - level_start,
+            };
+            // remember what the player sees on levels with limited visibility
+            cloned_level.reveal();
+            let mut highlight = match (hint, cloned_level.player_position()) {
+                (Some(Hint::Move(direction)), Some(player)) => vec![neighbour(player, direction)],
+                _ => vec![],
+            };
+            let walked = solver::walk(
+                &cloned_level,
+                path.make_contiguous(),
+                game_state.inventory(),
+            );
+            highlight.extend(walked.iter().copied());
+            if accessible {
+                let status = || {
+                    narration::status(
+                        &cloned_level,
+                        level_index + 1,
+                        info,
+                        elapsed.as_secs() as u128,
+                        game_state.inventory(),
+                        game_state.lives(),
+                    )
+                };
+                let surroundings = narration::surroundings(&cloned_level);
+                let snapshot = narration::Snapshot::new(&cloned_level);
+                let mut lines = vec![];
+                match &narrated {
+                    Some((before, said, help, paused)) => {
+                        lines.extend(narration::changes(before, &snapshot));
+                        if game_state.is_help() != *help {
+                            lines.push(status());
+                            lines.extend(info.hints.iter().cloned());
+                        }
+                        if game_state.is_paused() != *paused {
+                            lines.push(String::from(match game_state.is_paused() {
+                                true => "Paused, p = resume, q = quit",
+                                false => "Resumed",
+                            }));
+                        }
+                        if surroundings != *said {
+                            lines.push(surroundings.clone());
+                        }
+                    }
+                    None => {
+                        lines.push(status());
+                        lines.extend(cloned_level.message().map(String::from));
+                        lines.push(surroundings.clone());
+                    }
+                }
+                drawing.narrate(&lines)?;
+                narrated = Some((
+                    snapshot,
+                    surroundings,
+                    game_state.is_help(),
+                    game_state.is_paused(),
+                ));
+            } else {
+                drawing.draw_level(&cloned_level, &highlight)?;
+                drawing.draw_ui(
+                    level_index + 1,
+                    info,
+                    elapsed.as_secs() as u128,
+                    game_state.inventory(),
+                    game_state.lives(),
+                    max_y as u16,
+                )?;
+                if let Some(message) = cloned_level.message() {
+                    drawing.draw_message(message, max_y as u16)?;
+                }
+                if let Some(hint) = hint {
+                    drawing.draw_hint(hint, max_y as u16)?;
+                }
+                if let Some(notice) = &notice {
+                    drawing.draw_message(notice, max_y as u16)?;
+                }
+                if game_state.is_help() {
+                    drawing.draw_help(max_x as u16)?;
+                    drawing.draw_hints(max_x as u16, &info.hints)?;
+                }
+                if game_state.is_paused() {
+                    drawing.draw_pause(max_x as u16, max_y as u16)?;
+                }
+            }
+            drawing.flush()?;
+
+            let mut death = None;
+            if let Some(point) = &cloned_level.player_position() {
+                // any key cancels the path of a mouse click
+                let event = if let Some(event) = input.poll_event(Duration::from_millis(100))? {
+                    if let Event::Key(_) = event {
+                        path.clear();
+                    }
+                    Some(event)
+                } else if game_state.is_paused() {
+                    None
+                } else {
+                    // find a new way when the level changed under the path, like an enemy
+                    // or a closed door in the way, and stop when there is none
+                    if let (Some(target), false) = (path_target, path.is_empty()) {
+                        if walked.last() != Some(&target) {
+                            let searching = clock.now();
+                            path = match solver::path_to(
+                                &cloned_level,
+                                game_state.inventory(),
+                                target,
+                                HINT_STATES,
+                            ) {
+                                Solution::Path(moves) => moves.into(),
+                                _ => VecDeque::new(),
+                            };
+                            level_start += clock.now() - searching;
+                            last_enemy_move += clock.now() - searching;
+                        }
+                    }
+                    path.pop_front().map(|direction| {
+                        Event::Key(KeyEvent::new(arrow_key(direction), KeyModifiers::NONE))
+                    })
+                };
+                if let Some(event) = event {
+                    match event {
+                        // find a safe way to the clicked cell
+                        Event::Mouse(MouseEvent {
+                            kind: MouseEventKind::Down(MouseButton::Left),
+                            column,
+                            row,
+                            ..
+                        }) if !game_state.is_paused() => {
+                            hint = None;
+                            path.clear();
+                            if let (Ok(x), Ok(y)) = (i8::try_from(column), i8::try_from(row)) {
+                                let target = Point { x, y };
+                                if cloned_level.data.contains_key(&target) {
+                                    // the timers don't count the search, like for hints
+                                    let searching = clock.now();
+                                    let solution = solver::path_to(
+                                        &cloned_level,
+                                        game_state.inventory(),
+                                        target,
+                                        MAX_STATES,
+                                    );
+                                    level_start += clock.now() - searching;
+                                    last_enemy_move += clock.now() - searching;
+                                    if let Solution::Path(moves) = solution {
+                                        path = moves.into();
+                                        path_target = Some(target);
+                                    }
+                                }
+                            }
+                        }
+                        Event::Key(event) if game_state.is_paused() => {
+                            (game_state, _) = game_state.handle_key(event);
+                        }
+                        Event::FocusLost if !game_state.is_paused() => {
+                            game_state = allowed(game_state, game_state.pause())?;
+                        }
+                        // ask the solver for the next move from where we are now
+                        Event::Key(event) if event.code == KeyCode::Char('?') => {
+                            // the timers don't count the search
+                            let searching = clock.now();
+                            let next = solver::hint(&cloned_level, game_state.inventory());
+                            level_start += clock.now() - searching;
+                            last_enemy_move += clock.now() - searching;
+                            if accessible {
+                                drawing.narrate(&[String::from(hint_text(next))])?;
+                            }
+                            hint = Some(next);
+                            level_stats.hints += 1;
+                        }
+                        // save what is on the screen right now
+                        Event::Key(event) if event.code == KeyCode::Char('x') => {
+                            let mut saved = vec![];
+                            for format in [SnapshotFormat::Svg, SnapshotFormat::Html] {
+                                let path = format!("{}.{}", SNAPSHOT_FILE, format.extension());
+                                let contents =
+                                    snapshot(&cloned_level, format, &config.snapshot_options());
+                                std::fs::write(&path, contents)?;
+                                saved.push(path);
+                            }
+                            let text = format!("Snapshot saved to {}", saved.join(" and "));
+                            if accessible {
+                                drawing.narrate(std::slice::from_ref(&text))?;
+                            }
+                            notice = Some(text);
+                        }
+                        Event::Key(event) => {
+                            hint = None;
+                            notice = None;
+                            let command;
+                            (game_state, command) = game_state.handle_key(event);
+                            // This is the position the player wants to move
+                            let new_position = match command {
+                                Command::Move(direction) => neighbour(*point, direction),
+                                Command::Restart => {
+                                    level_stats.restarts += 1;
+                                    restart = true;
+                                    *point
+                                }
+                                Command::None => *point,
+                            };
+
+                            // collision forcing a restart when no powerup is active
+                            death = cloned_level.collision(*point, new_position);
+                            // update player position on the instance of the current level
+                            // here we do the validation and handle all the allowed moves.
+                            let mut inventory = game_state.inventory();
+                            // the player may slide further than the requested position
+                            let target = cloned_level.move_player(
+                                *point,
+                                new_position,
+                                max_x,
+                                max_y,
+                                &mut inventory,
+                            );
+                            // bumping into a wall is no move, stepping onto the exit is
+                            let moved = cloned_level.player_position() != Some(*point)
+                                || level.finish_position() == Some(target);
+                            if let (Command::Move(_), true) = (command, moved) {
+                                level_stats.moves += 1;
+                            }
+                            if level.finish_position() == Some(target) {
+                                game_state = allowed(game_state, game_state.complete())?;
+                                level_stats.time = (clock.now() - level_start).as_millis();
+                                level_stats.pickups = cloned_level.pickups();
+                                progress.record(level_index, level_stats.time);
+                                stats.push(level_stats);
+                            }
+                            // a player that died on the way is drawn where it wanted to go
+                            player = cloned_level.player_position().unwrap_or(new_position);
+                            game_state = game_state.new_inventory(inventory);
+                        }
+                        _ => {}
+                    }
+                }
+
+                // freeze the level timer and the enemy schedule while paused
+                match paused_at {
+                    Some(at) if !game_state.is_paused() => {
+                        level_start += clock.now() - at;
+                        last_enemy_move += clock.now() - at;
+                        paused_at = None;
+                    }
+                    None if game_state.is_paused() => paused_at = Some(clock.now()),
+                    _ => {}
+                }
+
+                // Move enemies after user input
+                // Only call this when we still have a player on the map
+                if !game_state.is_paused() && clock.now() - last_enemy_move >= enemy_move_interval {
+                    cloned_level.update_enemies();
+                    // conveyors move at the same pace as the enemies
+                    let mut inventory = game_state.inventory();
+                    if let Some(target) =
+                        cloned_level.update_conveyors(max_x, max_y, &mut inventory)
+                    {
+                        if game_state.is_playing() && level.finish_position() == Some(target) {
+                            game_state = allowed(game_state, game_state.complete())?;
+                            level_stats.time = (clock.now() - level_start).as_millis();
+                            level_stats.pickups = cloned_level.pickups();
+                            progress.record(level_index, level_stats.time);
+                            stats.push(level_stats);
+                        }
+                    }
+                    player = cloned_level.player_position().unwrap_or(player);
+                    game_state = game_state.new_inventory(inventory);
+                    last_enemy_move = clock.now();
+                }
+            // player was removed via enemy or void. force restart.
+            } else {
+                death = Some(cloned_level.death().unwrap_or(Death::Enemy));
+            }
+            if let Some(death) = death {
+                level_stats.deaths += 1;
+                game_state = die(drawing, clock, game_state, death, player, max_y, accessible)?;
+                restart = game_state.is_playing();
+            }
+        }
+        // quit or game over, the deaths and restarts on this level still count
+        if game_state.mode() != Mode::LevelComplete {
+            stats.push(LevelStats {
+                moves: 0,
+                unfinished: true,
+                ..level_stats
+            });
+        }
+    }
+    Ok(stats)
+}
+
+/// Play a run and show the results afterwards.
+pub fn play(
+    drawing: &mut Drawing<impl Write>,
+    input: &mut impl InputSource,
+    clock: &impl Clock,
+    config: &Config,
+    levels: impl IntoIterator<Item = Level>,
+    first_level: usize,
+    progress: &mut Progress,
+) -> Result<Vec<LevelStats>> {
+    let stats = game_loop(drawing, input, clock, levels, first_level, progress, config)?;
+    if let Some(path) = &config.export {
+        // the format was checked when reading the arguments
+        if let Some(format) = ExportFormat::from_path(path) {
+            std::fs::write(path, export(&stats, format))?;
+        }
+    }
+    if !stats.is_empty() {
+        drawing.reset()?;
+        if config.accessible {
+            drawing.narrate(&narration::results(&stats))?;
+        } else {
+            drawing.show_stats(&stats)?;
+        }
+        drawing.flush()?;
+        // wait for a key before going back to the menu
+        input.read_event()?;
+        drawing.init()?;
+    }
+    Ok(stats)
+}
+
+// Write a file of the menu. When that fails, like in a read-only directory, the game goes on
+// without it and the menu says so.
+fn save(path: &str, contents: String, notice: &mut Option<String>) {
+    if let Err(error) = std::fs::write(path, contents) {
+        *notice = Some(format!("Could not save {}: {}", path, error));
+    }
+}
+
+pub fn menu_loop(
+    mut drawing: Drawing<impl Write>,
+    mut input: impl InputSource,
+    clock: impl Clock,
+    mut config: Config,
+    levels: Vec<Level>,
+    mut progress: Progress,
+) -> Result<()> {
+    drawing.init()?;
+    let mut menu = Menu::new(levels.len(), config.lives);
+    // the accessible mode reads a screen out in full only when it is new
+    let mut narrated: Option<Screen> = None;
+    // a failed save, shown until the next key
+    let mut notice: Option<String> = None;
+    loop {
+        if config.accessible {
+            let whole = narrated != Some(menu.screen());
+            drawing.narrate(&narration::menu(&menu, &progress, whole))?;
+            drawing.narrate(notice.as_slice())?;
+            narrated = Some(menu.screen());
+        } else {
+            drawing.draw_menu(&menu, &progress, notice.as_deref())?;
+        }
+        drawing.flush()?;
+        if let Event::Key(event) = input.read_event()? {
+            notice = None;
+            menu = menu.update(event, progress.unlocked());
+            match menu.action() {
+                MenuAction::Play(level_index) => {
+                    play(
+                        &mut drawing,
+                        &mut input,
+                        &clock,
+                        &config,
+                        levels[level_index..].iter().cloned(),
+                        level_index,
+                        &mut progress,
+                    )?;
+                    save(SAVE_FILE, progress.to_file_string(), &mut notice);
+                }
+                MenuAction::PlaySingle(level_index) => {
+                    play(
+                        &mut drawing,
+                        &mut input,
+                        &clock,
+                        &config,
+                        levels[level_index..=level_index].iter().cloned(),
+                        level_index,
+                        &mut progress,
+                    )?;
+                    save(SAVE_FILE, progress.to_file_string(), &mut notice);
+                }
+                MenuAction::Quit => break,
+                MenuAction::None => {}
+            }
+            // keep settings changed in the menu for the next start
+            if menu.lives() != config.lives {
+                config.lives = menu.lives();
+                save(CONFIG_FILE, config.to_file_string(), &mut notice);
+            }
+        }
+    }
+    drawing.reset()?;
+    drawing.flush()?;
+    disable_raw_mode()
+}
+
+/// Play generated levels, getting harder every few rounds, until the player quits.
+pub fn endless(
+    mut drawing: Drawing<impl Write>,
+    mut input: impl InputSource,
+    clock: impl Clock,
+    config: &Config,
+    seed: u64,
+) -> Result<()> {
+    drawing.init()?;
+    // every round has its own seed, the run only ends when the player quits or loses
+    let levels = (0..).map(|round: usize| {
+        generate_retrying(
+            seed.wrapping_add(round as u64),
+            &GeneratorParams::for_round(round),
+        )
+        .expect("every round is big enough for a level")
+    });
+    // generated levels are not part of the saved progress
+    play(
+        &mut drawing,
+        &mut input,
+        &clock,
+        config,
+        levels,
+        0,
+        &mut Progress::new(),
+    )?;
+    drawing.reset()?;
+    drawing.flush()?;
+    disable_raw_mode()?;
+    println!("Seed: {}", seed);
+    Ok(())
+}
+
+/// One scored attempt at today's challenge. Quitting early still uses up the attempt.
+pub fn daily(
+    mut drawing: Drawing<impl Write>,
+    mut input: impl InputSource,
+    clock: impl Clock,
+    config: &Config,
+) -> Result<()> {
+    let today = Date::today();
+    let mut history = std::fs::read_to_string(DAILY_FILE)
+        .ok()
+        .and_then(|contents| DailyHistory::parse(&contents).ok())
+        .unwrap_or_default();
+    let stats = match history.result(today) {
+        Some(stats) => {
+            disable_raw_mode()?;
+            println!("You already played today's challenge.\n");
+            stats.clone()
+        }
+        None => {
+            history.record(today, &[]);
+            std::fs::write(DAILY_FILE, history.to_file_string())?;
+            drawing.init()?;
+            // daily levels are not part of the saved progress
+            let stats = play(
+                &mut drawing,
+                &mut input,
+                &clock,
+                config,
+                daily::levels(today),
+                0,
+                &mut Progress::new(),
+            )?;
+            history.record(today, &stats);
+            std::fs::write(DAILY_FILE, history.to_file_string())?;
+            drawing.reset()?;
+            drawing.flush()?;
+            disable_raw_mode()?;
+            stats
+        }
+    };
+    print!("{}", daily::summary(today, &stats));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::allowed;
+    use crate::utils::game_state::GameState;
+    use crate::utils::game_state::Mode;
+
+    #[test]
+    fn allowed_transition() {
+        let game_state = GameState::new();
+        let next = allowed(game_state, game_state.start()).unwrap();
+        assert_eq!(next.mode(), Mode::Playing);
+    }
+
+    #[test]
+    fn transition_not_allowed() {
+        let game_state = GameState::new();
+        let error = allowed(game_state, game_state.complete()).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("transition not allowed from Menu at src/game.rs:"));
+    }
+}
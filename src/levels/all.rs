@@ -1,10 +1,30 @@
 use super::level_1::level_1;
+use super::level_10::level_10;
+use super::level_11::level_11;
+use super::level_12::level_12;
 use super::level_2::level_2;
 use super::level_3::level_3;
 use super::level_4::level_4;
 use super::level_5::level_5;
+use super::level_6::level_6;
+use super::level_7::level_7;
+use super::level_8::level_8;
+use super::level_9::level_9;
 use crate::utils::level::Level;
 
 pub fn levels() -> Vec<Level> {
-    vec![level_1(), level_2(), level_3(), level_4(), level_5()]
+    vec![
+        level_1(),
+        level_2(),
+        level_3(),
+        level_4(),
+        level_5(),
+        level_6(),
+        level_7(),
+        level_8(),
+        level_9(),
+        level_10(),
+        level_11(),
+        level_12(),
+    ]
 }
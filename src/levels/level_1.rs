@@ -1,11 +1,18 @@
 use crate::utils::cell::Cell;
 use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
 use crate::utils::point::Point;
 use crate::utils::powerup::Powerup;
 pub fn level_1() -> Level {
     let mut level_data = Level::empty(4, 4);
     level_data.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
     level_data.update(Point { x: 3, y: 3 }, Cell::Exit);
+    level_data.set_info(LevelInfo {
+        intro: String::from("Walk to the exit X with the arrow keys."),
+        par_time: Some(10000),
+        par_moves: Some(6),
+        ..LevelInfo::new("First Steps")
+    });
     level_data
 }
 
@@ -0,0 +1,77 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+pub(crate) fn level_10() -> Level {
+    let mut l = Level::empty(8, 5);
+    l.update(Point { x: 0, y: 0 }, Cell::VerticalWall);
+    l.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::None));
+    for x in 2..8 {
+        l.update(Point { x, y: 0 }, Cell::HorizontalWall);
+    }
+
+    // run against the belt before it drops you into the void
+    l.update(Point { x: 0, y: 1 }, Cell::Void);
+    for x in 1..7 {
+        l.update(Point { x, y: 1 }, Cell::Conveyor(Direction::Left));
+    }
+    l.update(Point { x: 7, y: 1 }, Cell::Conveyor(Direction::Down));
+
+    for x in 0..7 {
+        l.update(Point { x, y: 2 }, Cell::Void);
+    }
+    l.update(Point { x: 7, y: 2 }, Cell::Conveyor(Direction::Down));
+
+    l.update(Point { x: 0, y: 4 }, Cell::Exit);
+    l.set_info(LevelInfo {
+        intro: String::from("Conveyor belts move everything on them every enemy tick."),
+        hints: vec![String::from("Walk against the belt.")],
+        par_time: Some(20000),
+        par_moves: Some(17),
+        ..LevelInfo::new("Conveyor")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::Direction;
+    use super::Powerup;
+    use crate::levels::level_10::level_10;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_10();
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Void) = level.data.get(&Point { x: 0, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Conveyor(Direction::Left)) = level.data.get(&Point { x: 1, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Conveyor(Direction::Down)) = level.data.get(&Point { x: 7, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Conveyor(Direction::Down)) = level.data.get(&Point { x: 7, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Void) = level.data.get(&Point { x: 6, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 0, y: 4 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+pub(crate) fn level_11() -> Level {
+    let mut l = Level::empty(11, 7);
+    // a dark maze, you only see three cells around you
+    l.set_visibility(Some(3));
+    l.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+
+    for y in 0..3 {
+        l.update(Point { x: 4, y }, Cell::VerticalWall);
+    }
+    for x in 0..3 {
+        l.update(Point { x, y: 1 }, Cell::HorizontalWall);
+    }
+    for x in 6..10 {
+        l.update(Point { x, y: 1 }, Cell::HorizontalWall);
+    }
+    for x in 1..5 {
+        l.update(Point { x, y: 3 }, Cell::HorizontalWall);
+    }
+    for y in 2..6 {
+        l.update(Point { x: 6, y }, Cell::VerticalWall);
+    }
+    for x in 8..11 {
+        l.update(Point { x, y: 3 }, Cell::HorizontalWall);
+    }
+    for x in 0..5 {
+        l.update(Point { x, y: 5 }, Cell::HorizontalWall);
+    }
+
+    l.update(Point { x: 9, y: 4 }, Cell::Void);
+    l.update(
+        Point { x: 9, y: 5 },
+        Cell::CounterClockwiseEnemy(Direction::Left),
+    );
+    l.update(Point { x: 0, y: 6 }, Cell::Exit);
+    l.set_info(LevelInfo {
+        intro: String::from("It is dark in here. You only see a few steps around you."),
+        hints: vec![String::from(
+            "Cells you have seen stay on the screen, dimmed.",
+        )],
+        par_time: Some(25000),
+        par_moves: Some(22),
+        // enemies are slower in the dark
+        enemy_interval: 700,
+        ..LevelInfo::new("In the Dark")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::Powerup;
+    use crate::levels::level_11::level_11;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_11();
+        assert_eq!(level.visibility(), Some(3));
+        assert_eq!(level.info().name, "In the Dark");
+        assert_eq!(level.info().enemy_interval, 700);
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::VerticalWall) = level.data.get(&Point { x: 4, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Void) = level.data.get(&Point { x: 9, y: 4 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 0, y: 6 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+}
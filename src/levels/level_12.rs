@@ -0,0 +1,98 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+use crate::utils::trigger::Action;
+use crate::utils::trigger::Trigger;
+use crate::utils::trigger::TriggerEvent;
+pub(crate) fn level_12() -> Level {
+    let mut l = Level::empty(12, 1);
+    l.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    l.update(Point { x: 10, y: 0 }, Cell::Door(3));
+    l.update(Point { x: 11, y: 0 }, Cell::Exit);
+
+    // halfway through the corridor the door opens, but only for a while
+    l.add_trigger(Trigger::new(
+        TriggerEvent::PlayerEntered(Point { x: 5, y: 0 }),
+        vec![
+            Action::OpenDoors(3),
+            Action::SpawnEnemy(Point { x: 0, y: 0 }, Direction::Right),
+            Action::ShowMessage(String::from(
+                "The door opens. Something woke up behind you.",
+            )),
+            Action::StartTimer { id: 0, ticks: 12 },
+        ],
+    ));
+    l.add_trigger(Trigger::new(
+        TriggerEvent::TimerExpired(0),
+        vec![
+            Action::CloseDoors(3),
+            Action::ShowMessage(String::from(
+                "Too slow, the door is shut. Press r to restart.",
+            )),
+        ],
+    ));
+    l.add_trigger(Trigger::repeating(
+        TriggerEvent::EnemyEntered(Point { x: 8, y: 0 }),
+        vec![Action::ShowMessage(String::from("It is right behind you!"))],
+    ));
+    l.set_info(LevelInfo {
+        intro: String::from("A long corridor and a closed door. What could go wrong?"),
+        hints: vec![String::from("Do not wait once the door opens.")],
+        par_time: Some(8000),
+        par_moves: Some(11),
+        ..LevelInfo::new("Ambush")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::Direction;
+    use super::Powerup;
+    use crate::levels::level_12::level_12;
+    use crate::utils::inventory::Inventory;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_12();
+        assert_eq!(level.info().name, "Ambush");
+        assert_eq!(level.triggers().len(), 3);
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Door(3)) = level.data.get(&Point { x: 10, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 11, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn door_opens_halfway() {
+        let mut level = level_12();
+        let mut inventory = Inventory::new();
+        for x in 1..6 {
+            level.move_player(
+                Point { x: x - 1, y: 0 },
+                Point { x, y: 0 },
+                11,
+                0,
+                &mut inventory,
+            );
+        }
+        assert!(level.data.get(&Point { x: 10, y: 0 }) == Some(&Cell::OpenDoor(3)));
+        assert!(
+            level.data.get(&Point { x: 0, y: 0 })
+                == Some(&Cell::CounterClockwiseEnemy(Direction::Right))
+        );
+        assert!(level.message().is_some());
+    }
+}
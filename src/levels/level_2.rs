@@ -1,5 +1,6 @@
 use crate::utils::cell::Cell;
 use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
 use crate::utils::point::Point;
 use crate::utils::powerup::Powerup;
 
@@ -24,6 +25,14 @@ pub(crate) fn level_2() -> Level {
 
     level_data.update(Point { x: 4, y: 3 }, Cell::Door(1));
     level_data.update(Point { x: 4, y: 4 }, Cell::Exit);
+    level_data.add_sign(Point { x: 0, y: 0 }, "Step on S to open D.");
+    level_data.set_info(LevelInfo {
+        intro: String::from("Doors D open when you step on the switch S of the same id."),
+        hints: vec![String::from("The switch is behind the wall.")],
+        par_time: Some(15000),
+        par_moves: Some(14),
+        ..LevelInfo::new("The Switch")
+    });
     level_data
 }
 #[cfg(test)]
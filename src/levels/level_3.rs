@@ -1,6 +1,7 @@
 use crate::utils::cell::Cell;
 use crate::utils::direction::Direction;
 use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
 use crate::utils::point::Point;
 use crate::utils::powerup::Powerup;
 pub(crate) fn level_3() -> Level {
@@ -36,6 +37,13 @@ pub(crate) fn level_3() -> Level {
     level_data.update(Point { x: 7, y: 1 }, Cell::Exit);
     level_data.update(Point { x: 7, y: 2 }, Cell::Void);
 
+    level_data.set_info(LevelInfo {
+        intro: String::from("Enemies § walk around and turn counter clockwise when blocked.\nDon't let them catch you."),
+        hints: vec![String::from("Wait for the guards to turn away.")],
+        par_time: Some(15000),
+        par_moves: Some(9),
+        ..LevelInfo::new("Guards")
+    });
     level_data
 }
 
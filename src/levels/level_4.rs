@@ -1,5 +1,6 @@
 use crate::utils::cell::Cell;
 use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
 use crate::utils::point::Point;
 use crate::utils::powerup::Powerup;
 pub(crate) fn level_4() -> Level {
@@ -86,5 +87,16 @@ pub(crate) fn level_4() -> Level {
     );
     level_data.update(Point { x: 24, y: 4 }, Cell::Player(Powerup::None));
     level_data.update(Point { x: 30, y: 23 }, Cell::Exit);
+    level_data.set_info(LevelInfo {
+        intro: String::from(
+            "Every row of teleporters stands for a day, a month or a year.\nPick the right date.",
+        ),
+        hints: vec![String::from(
+            "Only one teleporter in every row takes you further.",
+        )],
+        par_time: Some(60000),
+        par_moves: Some(73),
+        ..LevelInfo::new("Calendar")
+    });
     level_data
 }
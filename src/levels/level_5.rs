@@ -1,6 +1,7 @@
 use crate::utils::cell::Cell;
 use crate::utils::direction::Direction;
 use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
 use crate::utils::point::Point;
 use crate::utils::powerup::Powerup;
 pub(crate) fn level_5() -> Level {
@@ -211,5 +212,14 @@ pub(crate) fn level_5() -> Level {
         Cell::CounterClockwiseEnemy(Direction::Up),
     );
     l.update(Point { x: 8, y: 17 }, Cell::HorizontalWall);
+    l.set_info(LevelInfo {
+        intro: String::from("Breakable ground ? turns into void once you walked over it."),
+        hints: vec![String::from(
+            "Plan your way before you cross the breakable ground.",
+        )],
+        par_time: Some(60000),
+        par_moves: Some(72),
+        ..LevelInfo::new("Crumbling Ground")
+    });
     l
 }
@@ -0,0 +1,84 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+pub(crate) fn level_6() -> Level {
+    let mut l = Level::empty(10, 5);
+    l.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    // the toggle switch closes the door behind you and opens the one ahead
+    l.update(Point { x: 3, y: 0 }, Cell::OpenDoor(1));
+    l.update(Point { x: 5, y: 0 }, Cell::ToggleSwitch(1));
+    l.update(Point { x: 7, y: 0 }, Cell::Door(1));
+
+    for x in 0..10 {
+        l.update(Point { x, y: 1 }, Cell::HorizontalWall);
+        l.update(Point { x, y: 3 }, Cell::HorizontalWall);
+    }
+    // only open while the enemy stands on the pressure plate
+    l.update(Point { x: 8, y: 1 }, Cell::Door(2));
+
+    l.update(Point { x: 0, y: 2 }, Cell::VerticalWall);
+    l.update(
+        Point { x: 1, y: 2 },
+        Cell::CounterClockwiseEnemy(Direction::Right),
+    );
+    l.update(Point { x: 4, y: 2 }, Cell::PressurePlate(2));
+    l.update(Point { x: 9, y: 2 }, Cell::VerticalWall);
+
+    l.update(Point { x: 8, y: 3 }, Cell::Empty);
+    l.update(Point { x: 9, y: 4 }, Cell::Exit);
+    l.set_info(LevelInfo {
+        intro: String::from("A toggle switch s flips its doors. A pressure plate _ only holds its doors open\nwhile someone stands on it."),
+        hints: vec![String::from("The enemy can hold the pressure plate down for you.")],
+        par_time: Some(20000),
+        par_moves: Some(13),
+        ..LevelInfo::new("Under Pressure")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::Powerup;
+    use crate::levels::level_6::level_6;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_6();
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::OpenDoor(1)) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::ToggleSwitch(1)) = level.data.get(&Point { x: 5, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Door(1)) = level.data.get(&Point { x: 7, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Door(2)) = level.data.get(&Point { x: 8, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::PressurePlate(2)) = level.data.get(&Point { x: 4, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Empty) = level.data.get(&Point { x: 8, y: 3 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 9, y: 4 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+}
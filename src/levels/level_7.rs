@@ -0,0 +1,70 @@
+use crate::utils::cell::Cell;
+use crate::utils::key_color::KeyColor;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+pub(crate) fn level_7() -> Level {
+    let mut l = Level::empty(9, 5);
+    for y in 0..5 {
+        l.update(Point { x: 3, y }, Cell::VerticalWall);
+        l.update(Point { x: 6, y }, Cell::VerticalWall);
+    }
+    l.update(Point { x: 4, y: 0 }, Cell::Player(Powerup::None));
+    l.update(Point { x: 5, y: 4 }, Cell::Key(KeyColor::Red));
+
+    // the blue key is behind the red door, the exit behind the blue one
+    l.update(Point { x: 6, y: 2 }, Cell::LockedDoor(KeyColor::Red));
+    l.update(Point { x: 8, y: 0 }, Cell::Key(KeyColor::Blue));
+    l.update(Point { x: 3, y: 2 }, Cell::LockedDoor(KeyColor::Blue));
+    l.update(Point { x: 0, y: 0 }, Cell::Exit);
+    l.set_info(LevelInfo {
+        intro: String::from("Keys k open one locked door L of the same color."),
+        hints: vec![String::from("Not every key is needed.")],
+        par_time: Some(25000),
+        par_moves: Some(24),
+        ..LevelInfo::new("Keyring")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::KeyColor;
+    use super::Powerup;
+    use crate::levels::level_7::level_7;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_7();
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 4, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Key(KeyColor::Red)) = level.data.get(&Point { x: 5, y: 4 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::LockedDoor(KeyColor::Red)) = level.data.get(&Point { x: 6, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Key(KeyColor::Blue)) = level.data.get(&Point { x: 8, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::LockedDoor(KeyColor::Blue)) = level.data.get(&Point { x: 3, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::VerticalWall) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+}
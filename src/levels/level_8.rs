@@ -0,0 +1,70 @@
+use crate::utils::cell::Cell;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+pub(crate) fn level_8() -> Level {
+    let mut l = Level::empty(7, 6);
+    l.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    // push the box onto the plate to keep the door open
+    l.update(Point { x: 2, y: 0 }, Cell::Box);
+    l.update(Point { x: 4, y: 0 }, Cell::PressurePlate(1));
+
+    for x in 0..7 {
+        l.update(Point { x, y: 2 }, Cell::HorizontalWall);
+        l.update(Point { x, y: 4 }, Cell::Void);
+    }
+    l.update(Point { x: 5, y: 2 }, Cell::Door(1));
+
+    // and this one into the void to cross it
+    l.update(Point { x: 5, y: 3 }, Cell::Box);
+    l.update(Point { x: 0, y: 5 }, Cell::Exit);
+    l.set_info(LevelInfo {
+        intro: String::from("Push boxes # around. A box pushed into the void fills it."),
+        hints: vec![String::from("Don't push a box into a corner.")],
+        par_time: Some(20000),
+        par_moves: Some(15),
+        ..LevelInfo::new("Box Pusher")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::Powerup;
+    use crate::levels::level_8::level_8;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_8();
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Box) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::PressurePlate(1)) = level.data.get(&Point { x: 4, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Door(1)) = level.data.get(&Point { x: 5, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Box) = level.data.get(&Point { x: 5, y: 3 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Void) = level.data.get(&Point { x: 5, y: 4 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 0, y: 5 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::utils::cell::Cell;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+pub(crate) fn level_9() -> Level {
+    let mut l = Level::empty(7, 7);
+    for x in 0..7 {
+        for y in 0..7 {
+            l.update(Point { x, y }, Cell::Ice);
+        }
+    }
+    l.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+
+    // rocks to stop the slide
+    l.update(Point { x: 6, y: 0 }, Cell::VerticalWall);
+    l.update(Point { x: 3, y: 1 }, Cell::VerticalWall);
+    l.update(Point { x: 1, y: 3 }, Cell::VerticalWall);
+    l.update(Point { x: 5, y: 4 }, Cell::VerticalWall);
+    l.update(Point { x: 0, y: 6 }, Cell::VerticalWall);
+
+    l.update(Point { x: 6, y: 2 }, Cell::Void);
+    l.update(Point { x: 4, y: 6 }, Cell::Exit);
+    l.set_info(LevelInfo {
+        intro: String::from("On ice ~ you slide until something stops you."),
+        hints: vec![String::from("Walls are your brakes.")],
+        par_time: Some(10000),
+        par_moves: Some(4),
+        ..LevelInfo::new("Thin Ice")
+    });
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use super::Powerup;
+    use crate::levels::level_9::level_9;
+    use crate::utils::inventory::Inventory;
+    use crate::Point;
+    #[test]
+    fn cells() {
+        let level = level_9();
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Ice) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::VerticalWall) = level.data.get(&Point { x: 6, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::VerticalWall) = level.data.get(&Point { x: 3, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::VerticalWall) = level.data.get(&Point { x: 1, y: 3 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::VerticalWall) = level.data.get(&Point { x: 5, y: 4 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Void) = level.data.get(&Point { x: 6, y: 2 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Exit) = level.data.get(&Point { x: 4, y: 6 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn solution() {
+        let mut level = level_9();
+        let (max_x, max_y) = level.size();
+        let mut inventory = Inventory::new();
+        let mut target = Point { x: 0, y: 0 };
+        for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, 1), (1, 0)] {
+            let player = level.player_position().unwrap();
+            let new_position = Point {
+                x: player.x + dx,
+                y: player.y + dy,
+            };
+            target = level.move_player(player, new_position, max_x, max_y, &mut inventory);
+        }
+        assert_eq!(Some(target), level.finish_position());
+    }
+}
@@ -1,6 +1,13 @@
 pub mod all;
 mod level_1;
+mod level_10;
+mod level_11;
+mod level_12;
 mod level_2;
 mod level_3;
 mod level_4;
 mod level_5;
+mod level_6;
+mod level_7;
+mod level_8;
+mod level_9;
@@ -0,0 +1,45 @@
+//! The engine of Diamand Duo, a 2D ascii based terminal game.
+//!
+//! A [`Level`] is a grid of [`Cell`]s addressed by [`Point`]s. Moving the player with
+//! [`Level::move_player`] and ticking enemies and conveyors with [`Level::update_enemies`]
+//! and [`Level::update_conveyors`] is the whole simulation, nothing in it needs a terminal.
+//! The levels of the game are in [`levels::all`], random ones come from [`generate`]
+//! and [`solve`] finds the shortest way through any of them.
+//!
+//! ```
+//! use didu::{solve, Cell, Inventory, Level, Point, Powerup, Solution};
+//!
+//! // @.X
+//! let mut level = Level::empty(3, 1);
+//! level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+//! level.update(Point { x: 2, y: 0 }, Cell::Exit);
+//!
+//! let mut inventory = Inventory::new();
+//! let (max_x, max_y) = level.size();
+//! level.move_player(Point { x: 0, y: 0 }, Point { x: 1, y: 0 }, max_x, max_y, &mut inventory);
+//! assert_eq!(level.player_position(), Some(Point { x: 1, y: 0 }));
+//! assert!(matches!(solve(&level, inventory, 1000), Solution::Path(moves) if moves.len() == 1));
+//! ```
+//!
+//! The terminal game itself is in [`game`], the `didu` binary only reads the command line.
+
+pub mod game;
+pub mod levels;
+pub mod utils;
+
+pub use utils::cell::Cell;
+pub use utils::death::Death;
+pub use utils::direction::Direction;
+pub use utils::generator::generate;
+pub use utils::generator::GeneratorParams;
+pub use utils::inventory::Inventory;
+pub use utils::key_color::KeyColor;
+pub use utils::level::Level;
+pub use utils::level_info::LevelInfo;
+pub use utils::point::Point;
+pub use utils::powerup::Powerup;
+pub use utils::solver::solve;
+pub use utils::solver::Solution;
+pub use utils::trigger::Action;
+pub use utils::trigger::Trigger;
+pub use utils::trigger::TriggerEvent;
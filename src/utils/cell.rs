@@ -1,19 +1,82 @@
 use super::direction::Direction;
+use super::key_color::KeyColor;
 use super::point::Point;
 use super::powerup::Powerup;
 
-#[derive(Clone, PartialEq, Copy, Eq, Hash)]
+/// Everything that can be on a point of a level.
+#[derive(Clone, PartialEq, Copy, Eq, Hash, Debug)]
 pub enum Cell {
     Empty,
     Player(Powerup),
+    /// reaching it finishes the level
     Exit,
     HorizontalWall,
     VerticalWall,
+    /// moves in its direction every tick and turns counter clockwise when blocked
     CounterClockwiseEnemy(Direction),
+    /// falling into it kills the player
     Void,
+    /// opens the doors with the same id for good
     Switch(u8),
     Door(u8),
+    OpenDoor(u8),
+    /// opens the doors with the same id, or closes them again
+    ToggleSwitch(u8),
+    /// keeps the doors with the same id open while something stands on it
+    PressurePlate(u8),
+    /// picked up by walking over it, opens one locked door of its color
+    Key(KeyColor),
+    LockedDoor(KeyColor),
+    /// pushed by the player when there is room behind it, fills the void
+    Box,
+    /// the player keeps sliding over it
+    Ice,
+    /// moves whatever stands on it one cell every tick
+    Conveyor(Direction),
+    /// sends the player to the point
     OneWayTeleporter(Point),
+    /// turns into void once the player leaves it
     BreakableGround,
+    /// candy making the player invincible for a few moves, see `Powerup::Invincible`
     Invincibility,
+    /// shows its text from `Level::add_sign` when the player is on or next to it
+    Sign,
+}
+
+impl Cell {
+    /// What kind of cell it is, as told in the help, the legend of snapshots and the accessible mode.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Cell::Empty => "empty space",
+            Cell::Player(_) => "player",
+            Cell::Exit => "exit",
+            Cell::HorizontalWall | Cell::VerticalWall => "wall",
+            Cell::CounterClockwiseEnemy(_) => "enemy",
+            Cell::Void => "void",
+            Cell::Switch(_) => "switch",
+            Cell::Door(_) => "door",
+            Cell::OpenDoor(_) => "open door",
+            Cell::ToggleSwitch(_) => "toggle switch",
+            Cell::PressurePlate(_) => "pressure plate",
+            Cell::Key(_) => "key",
+            Cell::LockedDoor(_) => "locked door",
+            Cell::Box => "box",
+            Cell::Ice => "ice",
+            Cell::Conveyor(_) => "conveyor belt",
+            Cell::OneWayTeleporter(_) => "one way teleporter",
+            Cell::BreakableGround => "breakable ground",
+            Cell::Invincibility => "invincibility candy",
+            Cell::Sign => "sign",
+        }
+    }
+
+    /// The kind with the color of keys and locked doors, like "red key".
+    pub fn name(&self) -> String {
+        match self {
+            Cell::Key(color) | Cell::LockedDoor(color) => {
+                format!("{} {}", color.name(), self.kind())
+            }
+            _ => String::from(self.kind()),
+        }
+    }
 }
@@ -0,0 +1,96 @@
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Where the game takes the time from, for the level timer, the enemy schedule and animations.
+pub trait Clock {
+    /// Time since the clock was started.
+    fn now(&self) -> Duration;
+
+    /// Wait before going on, used between the frames of animations.
+    fn sleep(&self, duration: Duration);
+}
+
+/// The time of the system, what the game is played with.
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        RealClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        RealClock::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Time that only passes when it is told to, so tests know exactly how late it is.
+/// Clones share the same time: hand one to the game and keep one to move it forward.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Rc<std::cell::Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    // returns right away, with the time moved forward
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Clock;
+    use super::ManualClock;
+    use super::RealClock;
+    use std::time::Duration;
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+        let shared = clock.clone();
+        shared.advance(Duration::from_millis(500));
+        clock.sleep(Duration::from_millis(150));
+        assert_eq!(clock.now(), Duration::from_millis(650));
+        assert_eq!(shared.now(), Duration::from_millis(650));
+    }
+
+    #[test]
+    fn real_clock() {
+        let clock = RealClock::new();
+        let before = clock.now();
+        clock.sleep(Duration::from_millis(5));
+        assert!(clock.now() >= before + Duration::from_millis(5));
+    }
+}
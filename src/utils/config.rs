@@ -0,0 +1,344 @@
+use crate::utils::palette::Glyphs;
+use crate::utils::palette::Palette;
+use crate::utils::palette::PALETTE_NAMES;
+use crate::utils::recording::is_cast_file;
+use crate::utils::snapshot::SnapshotFormat;
+use crate::utils::snapshot::SnapshotOptions;
+use crate::utils::stats::ExportFormat;
+
+/// Settings chosen in the config file or on the command line.
+/// The command line wins over the config file.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Config {
+    /// None plays the casual mode with infinite retries
+    pub lives: Option<u8>,
+    /// write the results of every run to this csv or json file
+    pub export: Option<String>,
+    /// play generated levels until the player quits
+    pub endless: bool,
+    /// seed for the generated levels, taken from the clock when not set
+    pub seed: Option<u64>,
+    /// play today's daily challenge
+    pub daily: bool,
+    /// describe the level in text for screen readers instead of drawing it
+    pub accessible: bool,
+    /// colors of the map
+    pub palette: Palette,
+    /// characters of the map
+    pub glyphs: Glyphs,
+    /// render the start of a level, by its number, to a svg or html file and quit
+    pub snapshot: Option<(usize, String)>,
+    /// list every kind of cell below snapshots
+    pub legend: bool,
+    /// number the rows and columns of snapshots
+    pub grid: bool,
+    /// record everything drawn to this asciicast file
+    pub record: Option<String>,
+}
+
+/// Read at the start, settings changed in the menu are written back to it.
+pub const CONFIG_FILE: &str = "didu.conf";
+
+pub const USAGE: &str =
+    "Usage: didu [--lives <n>] [--casual] [--export <file.csv|file.json>] [--endless [--seed <n>]] [--daily] [--accessible] [--palette <name>] [--shapes] [--snapshot <level> <file.svg|file.html> [--legend] [--grid]] [--record <file.cast>]";
+
+fn parse_lives(value: &str) -> Result<Option<u8>, String> {
+    match value {
+        "infinite" => Ok(None),
+        _ => match value.parse::<u8>() {
+            Ok(lives) if lives > 0 => Ok(Some(lives)),
+            _ => Err(format!("Invalid number of lives: {}", value)),
+        },
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config {
+            lives: None,
+            export: None,
+            endless: false,
+            seed: None,
+            daily: false,
+            accessible: false,
+            palette: Palette::default(),
+            glyphs: Glyphs::Default,
+            snapshot: None,
+            legend: false,
+            grid: false,
+            record: None,
+        }
+    }
+
+    pub fn snapshot_options(&self) -> SnapshotOptions {
+        SnapshotOptions {
+            palette: self.palette,
+            glyphs: self.glyphs,
+            legend: self.legend,
+            grid: self.grid,
+        }
+    }
+
+    /// Read `key = value` lines. Empty lines and lines starting with `#` are ignored.
+    pub fn parse_file(&self, contents: &str) -> Result<Self, String> {
+        let mut config = self.clone();
+        for line in contents.lines().map(|line| line.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("lives", value)) => config.lives = parse_lives(value)?,
+                _ => return Err(format!("Invalid line in {}: {}", CONFIG_FILE, line)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Settings changed in the menu are written back to the config file
+    pub fn to_file_string(&self) -> String {
+        match self.lives {
+            Some(lives) => format!("lives = {}\n", lives),
+            None => String::from("lives = infinite\n"),
+        }
+    }
+
+    /// Read the command line arguments, without the program name.
+    pub fn parse_args(&self, args: &[String]) -> Result<Self, String> {
+        let mut config = self.clone();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--lives" => match args.next() {
+                    Some(value) => config.lives = parse_lives(value)?,
+                    None => return Err(String::from("Missing value for --lives")),
+                },
+                "--casual" => config.lives = None,
+                "--export" => match args.next() {
+                    Some(path) if ExportFormat::from_path(path).is_some() => {
+                        config.export = Some(path.clone())
+                    }
+                    Some(path) => return Err(format!("Unknown export format: {}", path)),
+                    None => return Err(String::from("Missing value for --export")),
+                },
+                "--endless" => config.endless = true,
+                "--daily" => config.daily = true,
+                "--accessible" => config.accessible = true,
+                "--shapes" => config.glyphs = Glyphs::Shapes,
+                "--legend" => config.legend = true,
+                "--grid" => config.grid = true,
+                "--record" => match args.next() {
+                    Some(path) if is_cast_file(path) => config.record = Some(path.clone()),
+                    Some(path) => return Err(format!("Recordings need a .cast file: {}", path)),
+                    None => return Err(String::from("Missing value for --record")),
+                },
+                "--snapshot" => match (args.next(), args.next()) {
+                    (Some(level), Some(path)) => {
+                        let level_number = match level.parse::<usize>() {
+                            Ok(level_number) if level_number > 0 => level_number,
+                            _ => return Err(format!("Invalid level: {}", level)),
+                        };
+                        if SnapshotFormat::from_path(path).is_none() {
+                            return Err(format!("Unknown snapshot format: {}", path));
+                        }
+                        config.snapshot = Some((level_number, path.clone()))
+                    }
+                    _ => return Err(String::from("Missing values for --snapshot")),
+                },
+                "--palette" => match args.next() {
+                    Some(name) => match Palette::from_name(name) {
+                        Some(palette) => config.palette = palette,
+                        None => {
+                            return Err(format!(
+                                "Unknown palette: {}, use one of {}",
+                                name,
+                                PALETTE_NAMES.join(", ")
+                            ))
+                        }
+                    },
+                    None => return Err(String::from("Missing value for --palette")),
+                },
+                "--seed" => match args.next() {
+                    Some(value) => match value.parse::<u64>() {
+                        Ok(seed) => config.seed = Some(seed),
+                        Err(_) => return Err(format!("Invalid seed: {}", value)),
+                    },
+                    None => return Err(String::from("Missing value for --seed")),
+                },
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::utils::palette::Glyphs;
+    use crate::utils::palette::Palette;
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(Config::new().lives, None);
+    }
+
+    #[test]
+    fn parse_file() {
+        let config = Config::new()
+            .parse_file("# lives mode\n\nlives = 3\n")
+            .unwrap();
+        assert_eq!(config.lives, Some(3));
+        let config = config.parse_file("lives=infinite").unwrap();
+        assert_eq!(config.lives, None);
+    }
+
+    #[test]
+    fn parse_file_invalid() {
+        assert!(Config::new().parse_file("lives = 0").is_err());
+        assert!(Config::new().parse_file("lives = many").is_err());
+        assert!(Config::new().parse_file("speed = 3").is_err());
+        assert!(Config::new().parse_file("lives").is_err());
+    }
+
+    #[test]
+    fn to_file_string() {
+        let config = Config {
+            lives: Some(4),
+            ..Config::new()
+        };
+        assert_eq!(config.to_file_string(), "lives = 4\n");
+        assert_eq!(
+            Config::new().parse_file(&config.to_file_string()),
+            Ok(config)
+        );
+        assert_eq!(Config::new().to_file_string(), "lives = infinite\n");
+    }
+
+    #[test]
+    fn parse_args() {
+        let config = Config::new().parse_args(&args(&["--lives", "5"])).unwrap();
+        assert_eq!(config.lives, Some(5));
+        let config = config.parse_args(&args(&["--casual"])).unwrap();
+        assert_eq!(config.lives, None);
+        let config = Config::new().parse_args(&args(&[])).unwrap();
+        assert_eq!(config, Config::new());
+    }
+
+    #[test]
+    fn parse_args_export() {
+        let config = Config::new()
+            .parse_args(&args(&["--export", "results.json"]))
+            .unwrap();
+        assert_eq!(config.export, Some(String::from("results.json")));
+        // the export is not stored in the config file
+        assert_eq!(config.to_file_string(), "lives = infinite\n");
+    }
+
+    #[test]
+    fn parse_args_endless() {
+        let config = Config::new()
+            .parse_args(&args(&["--endless", "--seed", "1234"]))
+            .unwrap();
+        assert!(config.endless);
+        assert_eq!(config.seed, Some(1234));
+        assert!(!Config::new().endless);
+    }
+
+    #[test]
+    fn parse_args_daily() {
+        assert!(Config::new().parse_args(&args(&["--daily"])).unwrap().daily);
+        assert!(!Config::new().daily);
+    }
+
+    #[test]
+    fn parse_args_accessible() {
+        assert!(
+            Config::new()
+                .parse_args(&args(&["--accessible"]))
+                .unwrap()
+                .accessible
+        );
+        assert!(!Config::new().accessible);
+    }
+
+    #[test]
+    fn parse_args_palette() {
+        let config = Config::new()
+            .parse_args(&args(&["--palette", "tritanopia", "--shapes"]))
+            .unwrap();
+        assert_eq!(config.palette, Palette::from_name("tritanopia").unwrap());
+        assert_eq!(config.glyphs, Glyphs::Shapes);
+        assert_eq!(Config::new().palette, Palette::default());
+        assert_eq!(Config::new().glyphs, Glyphs::Default);
+    }
+
+    #[test]
+    fn parse_args_snapshot() {
+        let config = Config::new()
+            .parse_args(&args(&["--snapshot", "3", "level.svg", "--grid"]))
+            .unwrap();
+        assert_eq!(config.snapshot, Some((3, String::from("level.svg"))));
+        let options = config.snapshot_options();
+        assert!(options.grid);
+        assert!(!options.legend);
+        assert_eq!(Config::new().snapshot, None);
+    }
+
+    #[test]
+    fn parse_args_record() {
+        let config = Config::new()
+            .parse_args(&args(&["--record", "session.cast"]))
+            .unwrap();
+        assert_eq!(config.record, Some(String::from("session.cast")));
+        assert_eq!(Config::new().record, None);
+    }
+
+    #[test]
+    fn parse_args_invalid() {
+        assert!(Config::new().parse_args(&args(&["--lives"])).is_err());
+        assert!(Config::new().parse_args(&args(&["--lives", "-1"])).is_err());
+        assert!(Config::new().parse_args(&args(&["--fast"])).is_err());
+        assert!(Config::new().parse_args(&args(&["--export"])).is_err());
+        assert!(Config::new().parse_args(&args(&["--seed"])).is_err());
+        assert!(Config::new().parse_args(&args(&["--seed", "abc"])).is_err());
+        assert!(Config::new().parse_args(&args(&["--palette"])).is_err());
+        assert!(Config::new()
+            .parse_args(&args(&["--snapshot", "1"]))
+            .is_err());
+        assert!(Config::new()
+            .parse_args(&args(&["--snapshot", "0", "level.svg"]))
+            .is_err());
+        assert!(Config::new()
+            .parse_args(&args(&["--snapshot", "1", "level.png"]))
+            .is_err());
+        assert!(Config::new().parse_args(&args(&["--record"])).is_err());
+        assert!(Config::new()
+            .parse_args(&args(&["--record", "session.txt"]))
+            .is_err());
+        assert!(Config::new()
+            .parse_args(&args(&["--palette", "sepia"]))
+            .is_err());
+        assert!(Config::new()
+            .parse_args(&args(&["--export", "results.txt"]))
+            .is_err());
+    }
+
+    #[test]
+    fn args_override_file() {
+        let config = Config::new()
+            .parse_file("lives = 3")
+            .unwrap()
+            .parse_args(&args(&["--casual"]))
+            .unwrap();
+        assert_eq!(config.lives, None);
+    }
+}
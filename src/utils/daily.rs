@@ -0,0 +1,367 @@
+use crate::utils::drawing::format_time;
+use crate::utils::generator::generate_retrying;
+use crate::utils::generator::GeneratorParams;
+use crate::utils::level::Level;
+use crate::utils::stats::total_score;
+use crate::utils::stats::totals;
+use crate::utils::stats::LevelStats;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+pub const DAILY_FILE: &str = "didu.daily";
+
+/// Number of generated levels in every daily challenge.
+pub const DAILY_LEVELS: usize = 5;
+
+/// A calendar day in UTC, so everyone plays the same levels at the same time.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Civil date from the days since 1970-01-01.
+    /// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date { year, month, day }
+    }
+
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or(0);
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    /// 2024-03-01 becomes 20240301
+    pub fn seed(&self) -> u64 {
+        (self.year * 10_000 + self.month as i64 * 100 + self.day as i64) as u64
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The fixed sequence of levels for a day, always `DAILY_LEVELS` of them so every run of
+/// the day can be compared.
+pub fn levels(date: Date) -> Vec<Level> {
+    (0..DAILY_LEVELS)
+        .map(|round| {
+            generate_retrying(
+                date.seed() * 100 + round as u64,
+                &GeneratorParams::for_round(round * 2),
+            )
+            .expect("every round is big enough for a level")
+        })
+        .collect()
+}
+
+/// Results of the daily challenges played on this computer, by date.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DailyHistory {
+    results: BTreeMap<String, Vec<LevelStats>>,
+}
+
+impl DailyHistory {
+    pub fn new() -> Self {
+        DailyHistory {
+            results: BTreeMap::new(),
+        }
+    }
+
+    pub fn result(&self, date: Date) -> Option<&Vec<LevelStats>> {
+        self.results.get(&date.to_string())
+    }
+
+    /// The levels played, the last one unfinished when the run ended on it.
+    /// An empty result is an attempt without any.
+    pub fn record(&mut self, date: Date, stats: &[LevelStats]) {
+        self.results.insert(date.to_string(), stats.to_vec());
+    }
+
+    /// Read `<date> = <level>; <level>` lines, with
+    /// `<time in ms> <moves> <deaths> <restarts> <pickups> <hints> <finished>` for every level
+    /// played, finished is 1 or 0.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut history = DailyHistory::new();
+        for line in contents.lines().map(|line| line.trim()) {
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || format!("Invalid line in {}: {}", DAILY_FILE, line);
+            let (date, value) = line
+                .split_once('=')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(invalid)?;
+            let mut stats = vec![];
+            for (level_index, level) in value
+                .split(';')
+                .filter(|level| !level.trim().is_empty())
+                .enumerate()
+            {
+                let fields: Vec<&str> = level.split_whitespace().collect();
+                let (time, counters, finished) = match fields[..] {
+                    [time, moves, deaths, restarts, pickups, hints, finished] => {
+                        (time, [moves, deaths, restarts, pickups, hints], finished)
+                    }
+                    _ => return Err(invalid()),
+                };
+                let counters = counters
+                    .iter()
+                    .map(|counter| counter.parse::<u32>().map_err(|_| invalid()))
+                    .collect::<Result<Vec<u32>, _>>()?;
+                stats.push(LevelStats {
+                    time: time.parse().map_err(|_| invalid())?,
+                    moves: counters[0],
+                    deaths: counters[1],
+                    restarts: counters[2],
+                    pickups: counters[3],
+                    hints: counters[4],
+                    unfinished: match finished {
+                        "0" => true,
+                        "1" => false,
+                        _ => return Err(invalid()),
+                    },
+                    ..LevelStats::new(level_index + 1)
+                });
+            }
+            history.results.insert(date.to_string(), stats);
+        }
+        Ok(history)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut contents = String::new();
+        for (date, stats) in &self.results {
+            let levels: Vec<String> = stats
+                .iter()
+                .map(|level| {
+                    format!(
+                        "{} {} {} {} {} {} {}",
+                        level.time,
+                        level.moves,
+                        level.deaths,
+                        level.restarts,
+                        level.pickups,
+                        level.hints,
+                        !level.unfinished as u8
+                    )
+                })
+                .collect();
+            contents.push_str(&format!("{} = {}\n", date, levels.join("; ")));
+        }
+        contents
+    }
+}
+
+impl Default for DailyHistory {
+    fn default() -> Self {
+        DailyHistory::new()
+    }
+}
+
+fn deaths(count: u32) -> String {
+    match count {
+        1 => String::from("1 death"),
+        _ => format!("{} deaths", count),
+    }
+}
+
+/// Plain text to share the result of a daily challenge with others.
+pub fn summary(date: Date, stats: &[LevelStats]) -> String {
+    let mut text = format!("Diamand Duo daily {}\n", date);
+    for level_number in 1..=DAILY_LEVELS {
+        match stats.get(level_number - 1) {
+            Some(level) if level.unfinished => text.push_str(&format!(
+                "Level {}: not finished, {}\n",
+                level_number,
+                deaths(level.deaths)
+            )),
+            Some(level) => text.push_str(&format!(
+                "Level {}: {}, {}\n",
+                level_number,
+                format_time(level.time),
+                deaths(level.deaths)
+            )),
+            None => text.push_str(&format!("Level {}: not finished\n", level_number)),
+        }
+    }
+    let total = totals(stats);
+    text.push_str(&format!(
+        "Total: {}, {}, score {}\n",
+        format_time(total.time),
+        deaths(total.deaths),
+        total_score(stats)
+    ));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DailyHistory;
+    use super::Date;
+    use super::DAILY_LEVELS;
+    use crate::utils::stats::LevelStats;
+
+    fn date() -> Date {
+        Date {
+            year: 2024,
+            month: 3,
+            day: 1,
+        }
+    }
+
+    fn stats() -> Vec<LevelStats> {
+        vec![
+            LevelStats {
+                time: 12345,
+                moves: 20,
+                deaths: 1,
+                ..LevelStats::new(1)
+            },
+            LevelStats {
+                time: 5000,
+                moves: 8,
+                restarts: 1,
+                pickups: 2,
+                ..LevelStats::new(2)
+            },
+        ]
+    }
+
+    #[test]
+    fn from_days() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        // leap day
+        assert_eq!(Date::from_days(19_782).to_string(), "2024-02-29");
+        assert_eq!(Date::from_days(19_783), date());
+        assert_eq!(Date::from_days(20_744).to_string(), "2026-10-18");
+    }
+
+    #[test]
+    fn seed() {
+        assert_eq!(date().seed(), 20240301);
+        assert_ne!(Date::from_days(1).seed(), Date::from_days(2).seed());
+    }
+
+    #[test]
+    fn levels() {
+        let levels = super::levels(date());
+        assert_eq!(levels.len(), DAILY_LEVELS);
+        // the same day always gives the same levels
+        let again = super::levels(date());
+        assert!(levels
+            .iter()
+            .zip(again.iter())
+            .all(|(a, b)| a.data == b.data));
+        let tomorrow = super::levels(Date::from_days(19_784));
+        assert!(levels[0].data != tomorrow[0].data);
+    }
+
+    #[test]
+    fn levels_every_day() {
+        // layouts that don't work are replaced, no day has a shorter challenge
+        for days in 19_700..19_730 {
+            assert_eq!(super::levels(Date::from_days(days)).len(), DAILY_LEVELS);
+        }
+    }
+
+    #[test]
+    fn history() {
+        let mut history = DailyHistory::new();
+        assert_eq!(history.result(date()), None);
+        history.record(date(), &stats());
+        assert_eq!(history.result(date()), Some(&stats()));
+        assert_eq!(
+            history.to_file_string(),
+            "2024-03-01 = 12345 20 1 0 0 0 1; 5000 8 0 1 2 0 1\n"
+        );
+        assert_eq!(DailyHistory::parse(&history.to_file_string()), Ok(history));
+    }
+
+    #[test]
+    fn history_without_finished_levels() {
+        let mut history = DailyHistory::new();
+        history.record(date(), &[]);
+        assert_eq!(history.to_file_string(), "2024-03-01 = \n");
+        let parsed = DailyHistory::parse(&history.to_file_string()).unwrap();
+        assert_eq!(parsed.result(date()), Some(&vec![]));
+    }
+
+    #[test]
+    fn parse_every_column() {
+        for line in [
+            "2024-03-01 = 12345 20 1 0 0",
+            "2024-03-01 = 12345 20 1 0 0 0",
+            "2024-03-01 = 12345 20 1 0 0 0 2",
+            "2024-03-01 = 12345 4294967296 1 0 0 0 1",
+        ] {
+            assert!(DailyHistory::parse(line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn unfinished_level() {
+        let mut played = stats();
+        played.push(LevelStats {
+            deaths: 3,
+            restarts: 1,
+            unfinished: true,
+            ..LevelStats::new(3)
+        });
+        let mut history = DailyHistory::new();
+        history.record(date(), &played);
+        assert!(history.to_file_string().ends_with("; 0 0 3 1 0 0 0\n"));
+        assert_eq!(DailyHistory::parse(&history.to_file_string()), Ok(history));
+        assert_eq!(
+            super::summary(date(), &played),
+            "Diamand Duo daily 2024-03-01\n\
+             Level 1: 00:12.345, 1 death\n\
+             Level 2: 00:05.000, 0 deaths\n\
+             Level 3: not finished, 3 deaths\n\
+             Level 4: not finished\n\
+             Level 5: not finished\n\
+             Total: 00:17.345, 4 deaths, score 1905\n"
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(DailyHistory::parse("2024-03-01").is_err());
+        assert!(DailyHistory::parse("2024-03-01 = 1 2 3").is_err());
+        assert!(DailyHistory::parse("2024-03-01 = a b c d e").is_err());
+        assert!(DailyHistory::parse("2024-03-01 = 1 2 3 4 5 6 7").is_err());
+    }
+
+    #[test]
+    fn summary() {
+        assert_eq!(
+            super::summary(date(), &stats()),
+            "Diamand Duo daily 2024-03-01\n\
+             Level 1: 00:12.345, 1 death\n\
+             Level 2: 00:05.000, 0 deaths\n\
+             Level 3: not finished\n\
+             Level 4: not finished\n\
+             Level 5: not finished\n\
+             Total: 00:17.345, 1 death, score 1905\n"
+        );
+    }
+}
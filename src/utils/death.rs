@@ -0,0 +1,16 @@
+/// How the player was removed from the map.
+#[derive(Clone, PartialEq, Copy, Eq, Debug)]
+pub enum Death {
+    Enemy,
+    Void,
+}
+
+impl Death {
+    /// Shown to the player when it happened.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Death::Enemy => "You were caught by an enemy!",
+            Death::Void => "You fell into the void!",
+        }
+    }
+}
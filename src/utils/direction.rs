@@ -1,4 +1,5 @@
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+/// A move of the player or an enemy, one cell at a time.
+#[derive(Clone, Copy, Eq, Hash, PartialEq, Debug)]
 pub enum Direction {
     Up,
     Down,
@@ -1,56 +1,331 @@
 use crate::utils::cell::Cell;
+use crate::utils::death::Death;
+use crate::utils::direction::Direction;
+use crate::utils::inventory::Inventory;
+use crate::utils::key_color::KeyColor;
 use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::menu::Menu;
+use crate::utils::menu::Screen;
+use crate::utils::menu::MENU_ENTRIES;
+use crate::utils::palette::Glyphs;
+use crate::utils::palette::Palette;
 use crate::utils::powerup::Powerup;
+use crate::utils::progress::Progress;
+use crate::utils::solver::Hint;
+use crate::utils::stats::total_score;
+use crate::utils::stats::totals;
+use crate::utils::stats::LevelStats;
 use crate::Point;
 use crossterm::{
     cursor,
     cursor::MoveTo,
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     queue,
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    style::{
+        Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
     terminal::{self, Clear, ClearType},
     ExecutableCommand, Result,
 };
+use std::collections::HashSet;
 use std::io::Write;
 
+// Format a duration in ms as mm:ss.mmm
+pub(crate) fn format_time(ms: u128) -> String {
+    format!("{:02}:{:02}.{:03}", ms / 60_000, ms / 1000 % 60, ms % 1000)
+}
+
+// Title and entries of the current menu screen
+pub(crate) fn menu_entries(menu: &Menu, progress: &Progress) -> (&'static str, Vec<String>) {
+    match menu.screen() {
+        Screen::Main => (
+            "Diamand Duo",
+            MENU_ENTRIES
+                .iter()
+                .map(|entry| entry.label().to_string())
+                .collect(),
+        ),
+        Screen::LevelSelect => (
+            "Level select",
+            (0..menu.level_count())
+                .map(|level_index| {
+                    let state = match progress.best_time(level_index) {
+                        Some(time) => format!("best {}", format_time(time)),
+                        None if progress.is_unlocked(level_index) => String::from("unlocked"),
+                        None => String::from("locked"),
+                    };
+                    format!("Level {:<3} {}", level_index + 1, state)
+                })
+                .collect(),
+        ),
+        Screen::Settings => (
+            "Settings",
+            vec![match menu.lives() {
+                Some(lives) => format!("Lives: {}", lives),
+                None => String::from("Lives: infinite"),
+            }],
+        ),
+    }
+}
+
+pub(crate) const MENU_KEYS: &str = "Arrow keys = move, Right = select, Left = back, q = quit";
+
+// Name, author, intro text and par of a level, shown before it starts
+pub(crate) fn intro_lines(level_number: usize, info: &LevelInfo) -> Vec<String> {
+    let mut lines = vec![format!("Level {}: {}", level_number, info.name)];
+    if !info.author.is_empty() {
+        lines.push(format!("by {}", info.author));
+    }
+    lines.push(String::new());
+    lines.extend(info.intro.lines().map(String::from));
+    lines.push(String::new());
+    match (info.par_time, info.par_moves) {
+        (Some(par_time), Some(par_moves)) => lines.push(format!(
+            "Par: {}, {} moves",
+            format_time(par_time),
+            par_moves
+        )),
+        (Some(par_time), None) => lines.push(format!("Par: {}", format_time(par_time))),
+        (None, Some(par_moves)) => lines.push(format!("Par: {} moves", par_moves)),
+        (None, None) => {}
+    }
+    lines.push(String::from("Press any key to start, q = quit"));
+    lines
+}
+
+pub(crate) fn hint_text(hint: Hint) -> &'static str {
+    match hint {
+        Hint::Move(Direction::Up) => "Hint: move up",
+        Hint::Move(Direction::Down) => "Hint: move down",
+        Hint::Move(Direction::Left) => "Hint: move left",
+        Hint::Move(Direction::Right) => "Hint: move right",
+        Hint::Unwinnable => "This state is unwinnable, restart with r.",
+        Hint::Unknown => "No hint found, you are on your own.",
+    }
+}
+
+// Every kind of cell in the help, with the symbol it is drawn with. Named by `Cell::kind`.
+const HELP_CELLS: [(&str, Cell, &str); 21] = [
+    (
+        "@",
+        Cell::Player(Powerup::None),
+        "Use Arrow keys to move around.",
+    ),
+    ("X", Cell::Exit, "You need to reach it."),
+    (".", Cell::Empty, "You can walk here."),
+    (" ", Cell::Void, "You should not walk on it."),
+    ("|", Cell::VerticalWall, "You can't pass it."),
+    ("-", Cell::HorizontalWall, "You can't pass it."),
+    ("S", Cell::Switch(0), "Opens its doors."),
+    (
+        "D",
+        Cell::Door(0),
+        "You need the correct switch to open it.",
+    ),
+    ("/", Cell::OpenDoor(0), "You can walk through it."),
+    (
+        "s",
+        Cell::ToggleSwitch(0),
+        "Opens and closes its doors every time.",
+    ),
+    (
+        "_",
+        Cell::PressurePlate(0),
+        "Keeps its doors open while held down.",
+    ),
+    (
+        "§",
+        Cell::CounterClockwiseEnemy(Direction::Up),
+        "Watch out!",
+    ),
+    (
+        "T",
+        Cell::OneWayTeleporter(Point { x: 0, y: 0 }),
+        "Sends you somewhere else.",
+    ),
+    (
+        "?",
+        Cell::BreakableGround,
+        "Will transfer to void after passed once.",
+    ),
+    ("o", Cell::Invincibility, "Makes you feel really strong!"),
+    (
+        "k",
+        Cell::Key(KeyColor::Red),
+        "Opens one locked door of the same color.",
+    ),
+    (
+        "L",
+        Cell::LockedDoor(KeyColor::Red),
+        "You need a key of the same color.",
+    ),
+    (
+        "#",
+        Cell::Box,
+        "Push it around, into the void or onto a pressure plate.",
+    ),
+    (
+        "~",
+        Cell::Ice,
+        "You slide over it until something stops you.",
+    ),
+    (
+        "<>^v",
+        Cell::Conveyor(Direction::Up),
+        "Moves everything on it in its direction.",
+    ),
+    ("!", Cell::Sign, "Step on or next to it to read it."),
+];
+
+// The keys of the help that are no moves
+const HELP_KEYS: [(&str, &str); 2] = [
+    ("p", "Pause the game. Timers and enemies stop."),
+    ("?", "Hint. Shows the next move towards the exit."),
+];
+
+// Drawn on the player position, one frame after another, when the player dies
+pub(crate) const DEATH_ANIMATION: [&str; 4] = ["*", "+", "x", " "];
+
+/// Draws the game to any writer, the terminal while playing.
 #[derive(Debug)]
-pub(crate) struct Drawing<W: Write> {
+pub struct Drawing<W: Write> {
     stdout: W,
+    palette: Palette,
+    glyphs: Glyphs,
 }
 
 impl<W: Write> Drawing<W> {
-    pub(crate) fn new(stdout: W) -> Self {
-        Drawing { stdout }
+    pub fn new(stdout: W) -> Self {
+        Drawing {
+            stdout,
+            palette: Palette::default(),
+            glyphs: Glyphs::Default,
+        }
+    }
+
+    pub fn with_palette(self, palette: Palette) -> Self {
+        Drawing { palette, ..self }
     }
-    pub(crate) fn show_timing(&mut self, timing: Vec<u128>) -> Result<()> {
-        queue!(self.stdout, MoveTo(0, 0), Print("Level | Time in ms"))?;
-        for (i, t) in timing.iter().enumerate() {
+
+    pub fn with_glyphs(self, glyphs: Glyphs) -> Self {
+        Drawing { glyphs, ..self }
+    }
+    /// Results of a run, one row per level played and the totals at the bottom.
+    pub fn show_stats(&mut self, stats: &[LevelStats]) -> Result<()> {
+        let row = |level: String, stats: &LevelStats, score: u32| {
+            // the level the run ended on has no time or moves
+            let (time, moves) = if stats.unfinished && stats.level_number > 0 {
+                (String::from("-"), String::from("-"))
+            } else {
+                (format_time(stats.time), stats.moves.to_string())
+            };
+            format!(
+                "{:>5} | {:>9} | {:>5} | {:>6} | {:>8} | {:>7} | {:>5} | {:>5}",
+                level, time, moves, stats.deaths, stats.restarts, stats.pickups, stats.hints, score
+            )
+        };
+        queue!(
+            self.stdout,
+            MoveTo(0, 0),
+            Print("Level | Time      | Moves | Deaths | Restarts | Pickups | Hints | Score")
+        )?;
+        for (i, level) in stats.iter().enumerate() {
             queue!(
                 self.stdout,
                 MoveTo(0, i as u16 + 1),
-                Print(format!("{:?} | {:?}\n", i, t))
+                Print(row(level.level_number.to_string(), level, level.score()))
             )?;
         }
+        queue!(
+            self.stdout,
+            MoveTo(0, stats.len() as u16 + 1),
+            Print(row(
+                String::from("Total"),
+                &totals(stats),
+                total_score(stats)
+            )),
+            MoveTo(0, stats.len() as u16 + 3),
+            Print("Press any key to continue")
+        )?;
         Ok(())
     }
 
-    pub(crate) fn flush(&mut self) -> Result<()> {
+    /// The menu screen with its entries and keys. The notice below the keys tells about
+    /// something that went wrong, like a failed save.
+    pub fn draw_menu(
+        &mut self,
+        menu: &Menu,
+        progress: &Progress,
+        notice: Option<&str>,
+    ) -> Result<()> {
+        let (title, entries) = menu_entries(menu, progress);
+        queue!(
+            self.stdout,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            SetForegroundColor(Color::Red),
+            Print(title),
+            ResetColor
+        )?;
+        for (index, entry) in entries.iter().enumerate() {
+            let marker = if index == menu.selected() { ">" } else { " " };
+            queue!(
+                self.stdout,
+                MoveTo(0, index as u16 + 2),
+                Print(format!("{} {}", marker, entry))
+            )?;
+        }
+        queue!(
+            self.stdout,
+            MoveTo(0, entries.len() as u16 + 3),
+            Print(MENU_KEYS)
+        )?;
+        if let Some(notice) = notice {
+            queue!(
+                self.stdout,
+                MoveTo(0, entries.len() as u16 + 5),
+                SetForegroundColor(Color::Cyan),
+                Print(notice),
+                ResetColor
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes everything queued so far.
+    pub fn flush(&mut self) -> Result<()> {
         self.stdout.flush()
     }
 
-    pub(crate) fn init(&mut self) -> Result<()> {
+    /// Prepares the terminal for playing: hidden cursor, focus and mouse events.
+    pub fn init(&mut self) -> Result<()> {
         self.stdout.execute(cursor::Hide)?;
+        // pause the game when the terminal loses focus
+        self.stdout.execute(EnableFocusChange)?;
+        // click a cell to walk there
+        self.stdout.execute(EnableMouseCapture)?;
         Ok(())
     }
-    pub(crate) fn reset(&mut self) -> Result<()> {
+
+    /// Undoes [`Drawing::init`] and clears the screen.
+    pub fn reset(&mut self) -> Result<()> {
+        self.stdout.execute(DisableMouseCapture)?;
+        self.stdout.execute(DisableFocusChange)?;
         self.stdout.execute(cursor::Show)?;
         self.stdout.execute(ResetColor)?;
         self.stdout.execute(terminal::Clear(ClearType::All))?;
         Ok(())
     }
-    pub(crate) fn draw_ui(
+
+    /// Status bar below the level: level, time, par, lives left and keys held.
+    pub fn draw_ui(
         &mut self,
         level_number: usize,
+        info: &LevelInfo,
         elapsed_time: u128,
+        inventory: Inventory,
+        lives: Option<u8>,
         max_y: u16,
     ) -> Result<()> {
         // Clear the status bar line
@@ -60,181 +335,301 @@ impl<W: Write> Drawing<W> {
             crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
         )?;
 
+        let name = match info.name.as_str() {
+            "" => String::new(),
+            name => format!(" - {}", name),
+        };
+        let par = match info.par_time {
+            Some(par_time) => format!(", Par: {}", par_time / 1000),
+            None => String::new(),
+        };
+        let lives = match lives {
+            Some(lives) => format!(", Lives: {}", lives),
+            None => String::new(),
+        };
+
+        // List every carried key by its color
+        let mut keys = String::new();
+        if !inventory.is_empty() {
+            keys.push_str(", Keys:");
+            for color in KeyColor::all() {
+                for _ in 0..inventory.count(color) {
+                    keys.push(' ');
+                    keys.push_str(color.name());
+                }
+            }
+        }
+
         // Print status bar
         queue!(
             self.stdout,
             MoveTo(0, max_y + 2),
             Print(format!(
-                "Level: {}, Time: {}, h = toggle help",
-                level_number, elapsed_time
+                "Level: {}{}, Time: {}{}{}{}, h = toggle help",
+                level_number, name, elapsed_time, par, lives, keys
             )),
         )?;
 
         Ok(())
     }
 
-    pub(crate) fn draw_help(&mut self, max_x: u16) -> Result<()> {
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 0),
-            Print("@ = Player - Use Arrow keys to move around.")
-        )?;
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 1),
-            Print("X = Exit that you need to reach.")
-        )?;
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 2),
-            Print(". = Empty Space - you can walk here.")
-        )?;
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 3),
-            Print("  = Void, you should not walk on it.")
-        )?;
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 4),
-            Print("| = Vertical wall that you can't pass.")
-        )?;
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 5),
-            Print("- = Horizontal wall that you can't pass.")
-        )?;
-        queue!(
-            self.stdout,
-            MoveTo(max_x + 4, 6),
-            Print("S = A switch that opens a door. ")
-        )?;
+    /// One frame of the death animation on the player, with the cause below the level.
+    pub fn draw_death(
+        &mut self,
+        position: Point,
+        death: Death,
+        frame: usize,
+        max_y: u16,
+    ) -> Result<()> {
         queue!(
             self.stdout,
-            MoveTo(max_x + 4, 7),
-            Print("D = A door. You need the correct Switch to open it.")
+            MoveTo(position.x as u16, position.y as u16),
+            SetForegroundColor(Color::Red),
+            Print(DEATH_ANIMATION[frame % DEATH_ANIMATION.len()]),
+            ResetColor,
+            MoveTo(0, max_y + 3),
+            Clear(ClearType::CurrentLine),
+            Print(death.message()),
         )?;
+        Ok(())
+    }
+
+    /// Next step towards the exit, below the status bar.
+    pub fn draw_hint(&mut self, hint: Hint, max_y: u16) -> Result<()> {
         queue!(
             self.stdout,
-            MoveTo(max_x + 4, 8),
-            Print("§ = An enemy! Watch out!")
+            MoveTo(0, max_y + 3),
+            Clear(ClearType::CurrentLine),
+            SetForegroundColor(Color::Yellow),
+            Print(hint_text(hint)),
+            ResetColor,
         )?;
+        Ok(())
+    }
+
+    /// Message of a trigger in the level, a hint is drawn over it.
+    pub fn draw_message(&mut self, message: &str, max_y: u16) -> Result<()> {
         queue!(
             self.stdout,
-            MoveTo(max_x + 4, 9),
-            Print("T = A one way teleporter. ")
+            MoveTo(0, max_y + 3),
+            Clear(ClearType::CurrentLine),
+            SetForegroundColor(Color::Cyan),
+            Print(message),
+            ResetColor,
         )?;
+        Ok(())
+    }
+
+    /// Plain lines for the accessible mode. Nothing is positioned or colored,
+    /// so a screen reader reads every line once, in order.
+    pub fn narrate(&mut self, lines: &[String]) -> Result<()> {
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            queue!(self.stdout, Print(line), Print("\r\n"))?;
+        }
+        Ok(())
+    }
+
+    /// Shown below the level when no lives are left.
+    pub fn draw_game_over(&mut self, max_y: u16) -> Result<()> {
         queue!(
             self.stdout,
-            MoveTo(max_x + 4, 10),
-            Print("? = Breakable ground. Will transfer to void after passed once.")
+            MoveTo(0, max_y + 4),
+            Clear(ClearType::CurrentLine),
+            SetForegroundColor(Color::Red),
+            Print("Game over! No lives left."),
+            ResetColor,
         )?;
+        Ok(())
+    }
+
+    /// Pause overlay in the middle of the level, with the allowed keys below the status bar.
+    pub fn draw_pause(&mut self, max_x: u16, max_y: u16) -> Result<()> {
         queue!(
             self.stdout,
-            MoveTo(max_x + 4, 11),
-            Print("o = Surprise Candy. Makes you feel really strong!")
+            MoveTo((max_x / 2).saturating_sub(3), max_y / 2),
+            SetForegroundColor(Color::Black),
+            SetBackgroundColor(Color::White),
+            Print(" PAUSED "),
+            ResetColor,
+            MoveTo(0, max_y + 3),
+            Clear(ClearType::CurrentLine),
+            Print("p = resume, q = quit"),
         )?;
         Ok(())
     }
 
-    pub(crate) fn draw_level(&mut self, level: &Level) -> Result<()> {
+    /// Shown before a level starts, when it has an intro.
+    pub fn draw_intro(&mut self, level_number: usize, info: &LevelInfo) -> Result<()> {
         queue!(self.stdout, Clear(ClearType::All))?;
+        for (row, line) in intro_lines(level_number, info).iter().enumerate() {
+            queue!(self.stdout, MoveTo(0, row as u16), Print(line))?;
+        }
+        Ok(())
+    }
+
+    /// Hints of the level below the help overlay
+    pub fn draw_hints(&mut self, max_x: u16, hints: &[String]) -> Result<()> {
+        if hints.is_empty() {
+            return Ok(());
+        }
+        queue!(self.stdout, MoveTo(max_x + 4, 23), Print("Hints:"))?;
+        for (i, hint) in hints.iter().enumerate() {
+            queue!(
+                self.stdout,
+                MoveTo(max_x + 4, 24 + i as u16),
+                Print(format!("- {}", hint))
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Legend of the cells and keys, right of the level.
+    pub fn draw_help(&mut self, max_x: u16) -> Result<()> {
+        let cells = HELP_CELLS.iter().map(|(symbol, cell, text)| {
+            let kind = cell.kind();
+            format!(
+                "{} = {}{}. {}",
+                symbol,
+                kind[..1].to_uppercase(),
+                &kind[1..],
+                text
+            )
+        });
+        let keys = HELP_KEYS
+            .iter()
+            .map(|(key, text)| format!("{} = {}", key, text));
+        for (row, line) in cells.chain(keys).enumerate() {
+            queue!(self.stdout, MoveTo(max_x + 4, row as u16), Print(line))?;
+        }
+        Ok(())
+    }
+
+    /// With limited visibility, unexplored cells stay hidden and
+    /// explored cells out of sight are drawn dimmed, without enemies.
+    /// Highlighted cells are drawn in reverse video.
+    pub fn draw_level(&mut self, level: &Level, highlight: &[Point]) -> Result<()> {
+        queue!(self.stdout, Clear(ClearType::All))?;
+        let visible = level.visible_cells();
         let mut keys: Vec<&Point> = level.data.keys().collect();
         keys.sort();
         for point in keys {
-            let cell = level.data.get(point).unwrap();
-            queue!(self.stdout, MoveTo(point.x as u16, point.y as u16))?;
-            match cell {
-                Cell::Empty => {
-                    queue!(self.stdout, SetForegroundColor(Color::Blue), Print("."))?;
-                }
-                Cell::Player(Powerup::None) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("@"))?;
-                }
-                Cell::Player(Powerup::Invincible(5)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("5"))?;
-                }
-                Cell::Player(Powerup::Invincible(4)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("4"))?;
-                }
-                Cell::Player(Powerup::Invincible(3)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("3"))?;
-                }
-                Cell::Player(Powerup::Invincible(2)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("2"))?;
-                }
-                Cell::Player(Powerup::Invincible(1)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("1"))?;
-                }
-                Cell::Player(Powerup::Invincible(0)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("0"))?;
-                }
-                Cell::Player(Powerup::Invincible(_)) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("@"))?;
-                }
-                Cell::Exit => {
-                    queue!(
-                        self.stdout,
-                        SetForegroundColor(Color::White),
-                        SetBackgroundColor(Color::Black),
-                        Print("X")
-                    )?;
-                }
-                Cell::Void => {
-                    queue!(
-                        self.stdout,
-                        SetForegroundColor(Color::Black),
-                        SetBackgroundColor(Color::Black),
-                        Print(" ")
-                    )?;
-                }
-                Cell::VerticalWall => {
-                    queue!(
-                        self.stdout,
-                        SetForegroundColor(Color::Grey),
-                        SetBackgroundColor(Color::Red),
-                        Print("|")
-                    )?;
-                }
-                Cell::HorizontalWall => {
-                    queue!(
-                        self.stdout,
-                        SetForegroundColor(Color::Grey),
-                        SetBackgroundColor(Color::Red),
-                        Print("-")
-                    )?;
-                }
-                Cell::Door(_) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Red), Print("D"))?;
-                }
-                Cell::Switch(_) => {
-                    queue!(self.stdout, SetForegroundColor(Color::Green), Print("S"))?;
-                }
-                Cell::CounterClockwiseEnemy(_) => {
-                    queue!(self.stdout, SetForegroundColor(Color::DarkRed), Print("§"))?;
-                }
-                Cell::OneWayTeleporter(_) => {
-                    queue!(self.stdout, SetForegroundColor(Color::DarkBlue), Print("T"))?;
-                }
-                Cell::BreakableGround => {
-                    queue!(self.stdout, SetForegroundColor(Color::Grey), Print("?"))?;
-                }
-                Cell::Invincibility => {
-                    queue!(
-                        self.stdout,
-                        SetBackgroundColor(Color::Yellow),
-                        SetForegroundColor(Color::White),
-                        Print("o")
-                    )?;
-                }
+            let (cell, remembered) = match shown_cell(level, &visible, *point) {
+                Some(shown) => shown,
+                None => continue,
+            };
+            if remembered {
+                queue!(self.stdout, SetAttribute(Attribute::Dim))?;
+            }
+            let highlighted = highlight.contains(point);
+            if highlighted {
+                queue!(self.stdout, SetAttribute(Attribute::Reverse))?;
+            }
+            let style = cell_style(cell, &self.palette, self.glyphs);
+            queue!(
+                self.stdout,
+                MoveTo(point.x as u16, point.y as u16),
+                SetForegroundColor(style.foreground)
+            )?;
+            if let Some(background) = style.background {
+                queue!(self.stdout, SetBackgroundColor(background))?;
+            }
+            queue!(self.stdout, Print(style.glyph), ResetColor)?;
+            if remembered || highlighted {
+                queue!(self.stdout, SetAttribute(Attribute::Reset))?;
             }
-            queue!(self.stdout, ResetColor)?;
         }
         self.stdout.flush()?; // Flush the queued commands
         Ok(())
     }
 }
 
+// What the player sees of a cell: the cell and whether it is only remembered.
+// None for cells the player has not seen yet.
+pub(crate) fn shown_cell(
+    level: &Level,
+    visible: &Option<HashSet<Point>>,
+    point: Point,
+) -> Option<(Cell, bool)> {
+    match visible {
+        Some(visible) if !visible.contains(&point) => {
+            if level.is_explored(point) {
+                level.remembered(point).map(|cell| (cell, true))
+            } else {
+                None
+            }
+        }
+        _ => level.data.get(&point).map(|cell| (*cell, false)),
+    }
+}
+
+// How a cell looks on the map
+pub(crate) struct CellStyle {
+    pub(crate) glyph: String,
+    pub(crate) foreground: Color,
+    pub(crate) background: Option<Color>,
+}
+
+pub(crate) fn cell_style(cell: Cell, palette: &Palette, glyphs: Glyphs) -> CellStyle {
+    let style = |glyph: &str, foreground: Color, background: Option<Color>| CellStyle {
+        glyph: String::from(glyph),
+        foreground,
+        background,
+    };
+    match cell {
+        Cell::Empty => style(".", palette.empty, None),
+        // the moves left of the invincibility
+        Cell::Player(Powerup::Invincible(moves)) if moves <= 5 => {
+            style(&moves.to_string(), palette.player, None)
+        }
+        Cell::Player(_) => style("@", palette.player, None),
+        Cell::Exit => style("X", Color::White, Some(Color::Black)),
+        Cell::Void => {
+            let glyph = match glyphs {
+                Glyphs::Default => " ",
+                Glyphs::Shapes => "░",
+            };
+            style(glyph, Color::DarkGrey, Some(Color::Black))
+        }
+        Cell::VerticalWall => style("|", Color::Grey, Some(palette.wall)),
+        Cell::HorizontalWall => style("-", Color::Grey, Some(palette.wall)),
+        Cell::Door(_) => style("D", palette.door, None),
+        Cell::OpenDoor(_) => style("/", palette.door, None),
+        Cell::Switch(_) => style("S", palette.switch, None),
+        Cell::ToggleSwitch(_) => style("s", palette.switch, None),
+        Cell::PressurePlate(_) => style("_", palette.switch, None),
+        Cell::Box => style("#", palette.crate_box, None),
+        Cell::Ice => style("~", Color::White, Some(palette.ice)),
+        Cell::Conveyor(direction) => {
+            let arrow = match direction {
+                Direction::Up => "^",
+                Direction::Down => "v",
+                Direction::Left => "<",
+                Direction::Right => ">",
+            };
+            style(arrow, palette.conveyor, None)
+        }
+        Cell::Key(color) => {
+            let glyph = match glyphs {
+                Glyphs::Default => "k",
+                Glyphs::Shapes => &color.name()[..1],
+            };
+            style(glyph, palette.key(color), None)
+        }
+        Cell::LockedDoor(color) => {
+            let glyph = match glyphs {
+                Glyphs::Default => String::from("L"),
+                Glyphs::Shapes => color.name()[..1].to_uppercase(),
+            };
+            style(&glyph, Color::Black, Some(palette.key(color)))
+        }
+        Cell::CounterClockwiseEnemy(_) => style("§", palette.enemy, None),
+        Cell::OneWayTeleporter(_) => style("T", palette.teleporter, None),
+        Cell::BreakableGround => style("?", palette.breakable_ground, None),
+        Cell::Invincibility => style("o", Color::White, Some(palette.candy)),
+        Cell::Sign => style("!", palette.sign, None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -242,9 +637,26 @@ mod tests {
     use crate::utils::level::Level;
 
     use super::Cell;
+    use super::Death;
     use super::Drawing;
+    use super::Glyphs;
+    use super::Hint;
+    use super::Inventory;
+    use super::KeyColor;
+    use super::LevelInfo;
+    use super::LevelStats;
+    use super::Menu;
+    use super::Palette;
     use super::Powerup;
+    use super::Progress;
     use crate::Point;
+    use crossterm::event::KeyCode;
+    use crossterm::event::KeyEvent;
+    use crossterm::event::KeyModifiers;
+    use crossterm::queue;
+    use crossterm::style::Color;
+    use crossterm::style::Print;
+    use crossterm::style::SetForegroundColor;
     use crossterm::Result;
     use regex::Regex;
     fn strip_ansi_codes(s: &str) -> String {
@@ -253,18 +665,33 @@ mod tests {
     }
 
     #[test]
-    fn show_timing() -> Result<()> {
+    fn show_stats() -> Result<()> {
         // Prepare a buffer to capture the output
         let mut buffer = Vec::new();
 
         // Initialize the Drawing object with the buffer
         let mut drawing = Drawing::new(&mut buffer);
 
-        // Data to be passed to the show_timing method for testing
-        let timing_data = vec![100, 200, 300];
+        // Data to be passed to the show_stats method for testing
+        let stats = vec![
+            LevelStats {
+                time: 61234,
+                moves: 12,
+                deaths: 1,
+                pickups: 1,
+                ..LevelStats::new(1)
+            },
+            LevelStats {
+                time: 2000,
+                moves: 3,
+                restarts: 2,
+                hints: 1,
+                ..LevelStats::new(2)
+            },
+        ];
 
         // Call the method to be tested
-        drawing.show_timing(timing_data)?;
+        drawing.show_stats(&stats)?;
 
         // Convert the buffer to a String to verify its contents
         let output = String::from_utf8(buffer).unwrap();
@@ -272,8 +699,14 @@ mod tests {
         // Remove Ascii
         let escaped_output = strip_ansi_codes(&output);
 
-        // Expected output based on the timing_data
-        let expected_output = "Level | Time in ms0 | 100\n1 | 200\n2 | 300\n";
+        // Expected output based on the stats
+        let expected_output = concat!(
+            "Level | Time      | Moves | Deaths | Restarts | Pickups | Hints | Score",
+            "    1 | 01:01.234 |    12 |      1 |        0 |       1 |     0 |   877",
+            "    2 | 00:02.000 |     3 |      0 |        2 |       0 |     1 |   895",
+            "Total | 01:03.234 |    15 |      1 |        2 |       1 |     1 |  1772",
+            "Press any key to continue"
+        );
 
         // Assert that the method works as expected
         assert_eq!(escaped_output, expected_output);
@@ -281,6 +714,66 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn format_time() {
+        assert_eq!(super::format_time(0), "00:00.000");
+        assert_eq!(super::format_time(61_042), "01:01.042");
+        assert_eq!(super::format_time(600_000), "10:00.000");
+    }
+
+    #[test]
+    fn draw_menu() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_menu(&Menu::new(3, None), &Progress::new(), None);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        for entry in [
+            "Diamand Duo",
+            "> Play",
+            "  Continue",
+            "  Level select",
+            "  Settings",
+            "  Quit",
+        ] {
+            assert!(escaped_output.contains(entry));
+        }
+    }
+
+    #[test]
+    fn draw_menu_with_notice() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_menu(
+            &Menu::new(3, None),
+            &Progress::new(),
+            Some("Could not save didu.save"),
+        );
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(strip_ansi_codes(&output).ends_with("q = quitCould not save didu.save"));
+    }
+
+    #[test]
+    fn draw_level_select() {
+        let mut progress = Progress::new();
+        progress.record(0, 61_042);
+        let mut menu = Menu::new(3, None);
+        for code in [KeyCode::Down, KeyCode::Down, KeyCode::Right] {
+            menu = menu.update(KeyEvent::new(code, KeyModifiers::NONE), progress.unlocked());
+        }
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_menu(&menu, &progress, None);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert!(escaped_output.contains("> Level 1   best 01:01.042"));
+        assert!(escaped_output.contains("  Level 2   unlocked"));
+        assert!(escaped_output.contains("  Level 3   locked"));
+    }
+
     #[test]
     fn flush() {
         let buffer = Vec::new();
@@ -329,9 +822,21 @@ mod tests {
         level.update(Point { x: 16, y: 0 }, Cell::Player(Powerup::Invincible(1)));
         level.update(Point { x: 17, y: 0 }, Cell::Player(Powerup::Invincible(0)));
         level.update(Point { x: 18, y: 0 }, Cell::Player(Powerup::Invincible(6)));
+        level.update(Point { x: 19, y: 0 }, Cell::OpenDoor(1));
+        level.update(Point { x: 20, y: 0 }, Cell::ToggleSwitch(1));
+        level.update(Point { x: 21, y: 0 }, Cell::PressurePlate(1));
+        level.update(Point { x: 22, y: 0 }, Cell::Key(KeyColor::Red));
+        level.update(Point { x: 23, y: 0 }, Cell::LockedDoor(KeyColor::Blue));
+        level.update(Point { x: 24, y: 0 }, Cell::Box);
+        level.update(Point { x: 25, y: 0 }, Cell::Ice);
+        level.update(Point { x: 26, y: 0 }, Cell::Conveyor(Direction::Left));
+        level.update(Point { x: 27, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.update(Point { x: 28, y: 0 }, Cell::Conveyor(Direction::Up));
+        level.update(Point { x: 29, y: 0 }, Cell::Conveyor(Direction::Down));
+        level.update(Point { x: 30, y: 0 }, Cell::Sign);
         let mut buffer = Vec::new();
         let mut drawing = Drawing::new(&mut buffer);
-        let _ = drawing.draw_level(&level);
+        let _ = drawing.draw_level(&level, &[]);
         let _ = drawing.flush();
         let output = String::from_utf8(buffer).unwrap();
 
@@ -339,12 +844,66 @@ mod tests {
         let escaped_output = strip_ansi_codes(&output);
 
         // Expected output based on the Cells
-        let expected_output = ".@X-| DSo?T§543210@";
+        let expected_output = ".@X-| DSo?T§543210@/s_kL#~<>^v!";
 
         // Assert that the method works as expected
         assert_eq!(escaped_output, expected_output);
     }
 
+    #[test]
+    fn drawing_level_with_shapes() {
+        let mut level = Level::empty(1, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Void);
+        level.update(Point { x: 1, y: 0 }, Cell::Key(KeyColor::Red));
+        level.update(Point { x: 2, y: 0 }, Cell::Key(KeyColor::Green));
+        level.update(Point { x: 3, y: 0 }, Cell::LockedDoor(KeyColor::Blue));
+        level.update(Point { x: 4, y: 0 }, Cell::LockedDoor(KeyColor::Yellow));
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer).with_glyphs(Glyphs::Shapes);
+        let _ = drawing.draw_level(&level, &[]);
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(strip_ansi_codes(&output), "░rgBY");
+    }
+
+    #[test]
+    fn drawing_level_with_palette() {
+        let mut level = Level::empty(1, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        let mut buffer = Vec::new();
+        let palette = Palette {
+            player: Color::Yellow,
+            ..Palette::default()
+        };
+        let mut drawing = Drawing::new(&mut buffer).with_palette(palette);
+        let _ = drawing.draw_level(&level, &[]);
+        let output = String::from_utf8(buffer).unwrap();
+        let mut expected = Vec::new();
+        let _ = queue!(expected, SetForegroundColor(Color::Yellow), Print("@"));
+        assert!(output.contains(&String::from_utf8(expected).unwrap()));
+    }
+
+    #[test]
+    fn drawing_level_with_fog() {
+        // @.§.
+        let mut level = Level::empty(4, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(
+            Point { x: 2, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Left),
+        );
+        level.set_visibility(Some(2));
+        level.reveal();
+        level.set_visibility(Some(1));
+
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_level(&level, &[]);
+        let output = String::from_utf8(buffer).unwrap();
+        // the enemy out of sight is remembered as empty ground, the last cell was never seen
+        assert_eq!(strip_ansi_codes(&output), "@..");
+        assert!(output.contains("\x1b[2m"));
+    }
+
     #[test]
     fn draw_help() {
         let mut buffer = Vec::new();
@@ -354,21 +913,196 @@ mod tests {
         let output = String::from_utf8(buffer).unwrap();
         let escaped_output = strip_ansi_codes(&output);
         // expect all cells are explained in the help overview.
-        let cell_types = vec!["@", "X", "|", "-", "D", "S", "o", "?"];
+        let cell_types = vec![
+            "@", "X", "|", "-", "D", "S", "o", "?", "s", "_", "/", "k", "L", "#", "~", "<", ">",
+            "^", "v", "!",
+        ];
         for cell_type in cell_types {
             assert!(escaped_output.contains(cell_type))
         }
+        // named like in the legend of snapshots
+        assert!(escaped_output.contains("D = Door. "));
+        assert!(escaped_output.contains("k = Key. "));
     }
 
     #[test]
     fn draw_ui() {
         let mut buffer = Vec::new();
         let mut drawing = Drawing::new(&mut buffer);
-        let _ = drawing.draw_ui(1, 123456, 5);
+        let _ = drawing.draw_ui(1, &LevelInfo::default(), 123456, Inventory::new(), None, 5);
         let _ = drawing.flush();
         let output = String::from_utf8(buffer).unwrap();
         let escaped_output = strip_ansi_codes(&output);
         let expected_output = "Level: 1, Time: 123456, h = toggle help";
         assert_eq!(escaped_output, expected_output);
     }
+
+    #[test]
+    fn draw_ui_with_info() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let info = LevelInfo {
+            par_time: Some(12_000),
+            ..LevelInfo::new("First steps")
+        };
+        let _ = drawing.draw_ui(1, &info, 4, Inventory::new(), None, 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        let expected_output = "Level: 1 - First steps, Time: 4, Par: 12, h = toggle help";
+        assert_eq!(escaped_output, expected_output);
+    }
+
+    #[test]
+    fn draw_intro() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let info = LevelInfo {
+            author: String::from("someone"),
+            intro: String::from("Reach the exit.\nMind the void."),
+            par_time: Some(5_000),
+            par_moves: Some(6),
+            ..LevelInfo::new("First steps")
+        };
+        let _ = drawing.draw_intro(1, &info);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert_eq!(
+            escaped_output,
+            concat!(
+                "Level 1: First steps",
+                "by someone",
+                "Reach the exit.",
+                "Mind the void.",
+                "Par: 00:05.000, 6 moves",
+                "Press any key to start, q = quit"
+            )
+        );
+    }
+
+    #[test]
+    fn draw_hints() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_hints(3, &[]);
+        let _ = drawing.draw_hints(3, &[String::from("Go around.")]);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert_eq!(escaped_output, "Hints:- Go around.");
+    }
+
+    #[test]
+    fn draw_ui_with_keys() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let mut inventory = Inventory::new();
+        inventory.add(KeyColor::Blue);
+        inventory.add(KeyColor::Red);
+        inventory.add(KeyColor::Blue);
+        let _ = drawing.draw_ui(2, &LevelInfo::default(), 5, inventory, None, 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        let expected_output = "Level: 2, Time: 5, Keys: red blue blue, h = toggle help";
+        assert_eq!(escaped_output, expected_output);
+    }
+
+    #[test]
+    fn draw_ui_with_lives() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_ui(3, &LevelInfo::default(), 7, Inventory::new(), Some(2), 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        let expected_output = "Level: 3, Time: 7, Lives: 2, h = toggle help";
+        assert_eq!(escaped_output, expected_output);
+    }
+
+    #[test]
+    fn draw_death() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_death(Point { x: 1, y: 1 }, Death::Void, 0, 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert_eq!(escaped_output, "*You fell into the void!");
+    }
+
+    #[test]
+    fn draw_pause() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_pause(10, 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert_eq!(escaped_output, " PAUSED p = resume, q = quit");
+    }
+
+    #[test]
+    fn draw_hint() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_hint(Hint::Move(Direction::Left), 5);
+        let _ = drawing.draw_hint(Hint::Unwinnable, 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert_eq!(
+            escaped_output,
+            "Hint: move leftThis state is unwinnable, restart with r."
+        );
+    }
+
+    #[test]
+    fn draw_message() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_message("The door behind you closes.", 5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(strip_ansi_codes(&output), "The door behind you closes.");
+    }
+
+    #[test]
+    fn narrate() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.narrate(&[
+            String::from("door 2 opened"),
+            String::new(),
+            String::from("wall north"),
+        ]);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "door 2 opened\r\nwall north\r\n");
+    }
+
+    #[test]
+    fn drawing_level_with_highlight() {
+        let mut level = Level::empty(2, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_level(&level, &[Point { x: 1, y: 0 }]);
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(strip_ansi_codes(&output), "@.");
+        // reverse video right before the highlighted cell
+        assert!(output.contains("\x1b[7m\x1b[1;2H"));
+    }
+
+    #[test]
+    fn draw_game_over() {
+        let mut buffer = Vec::new();
+        let mut drawing = Drawing::new(&mut buffer);
+        let _ = drawing.draw_game_over(5);
+        let _ = drawing.flush();
+        let output = String::from_utf8(buffer).unwrap();
+        let escaped_output = strip_ansi_codes(&output);
+        assert_eq!(escaped_output, "Game over! No lives left.");
+    }
 }
@@ -1,315 +1,450 @@
-use crate::utils::point::Point;
+use crate::utils::death::Death;
+use crate::utils::direction::Direction;
+use crate::utils::inventory::Inventory;
 
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 
+/// Where a run of levels is at. Only the transitions in `Mode::next` lead from one to another.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    /// back on the title screen, the run is over
+    Menu,
+    Playing,
+    /// timers, enemies and input are frozen
+    Paused,
+    /// the death animation is playing, a life is taken afterwards
+    Dying(Death),
+    /// the exit was reached, the next level starts from here
+    LevelComplete,
+    /// no lives left
+    GameOver,
+}
+
+/// Everything that moves the game from one mode to another.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transition {
+    /// play the first or the next level
+    Start,
+    Pause,
+    Resume,
+    /// start the current level over
+    Restart,
+    Die(Death),
+    /// try the level again after dying
+    Respawn,
+    /// died with the last life
+    Lose,
+    Complete,
+    Quit,
+}
+
+impl Mode {
+    /// The mode after the transition, None when it is not allowed from here.
+    pub fn next(self, transition: Transition) -> Option<Mode> {
+        match (self, transition) {
+            (Mode::Menu | Mode::LevelComplete, Transition::Start) => Some(Mode::Playing),
+            (Mode::Playing, Transition::Pause) => Some(Mode::Paused),
+            (Mode::Paused, Transition::Resume) => Some(Mode::Playing),
+            (Mode::Playing, Transition::Restart) => Some(Mode::Playing),
+            (Mode::Playing, Transition::Die(death)) => Some(Mode::Dying(death)),
+            (Mode::Dying(_), Transition::Respawn) => Some(Mode::Playing),
+            (Mode::Dying(_), Transition::Lose) => Some(Mode::GameOver),
+            (Mode::Playing, Transition::Complete) => Some(Mode::LevelComplete),
+            (Mode::Playing | Mode::Paused, Transition::Quit) => Some(Mode::Menu),
+            _ => None,
+        }
+    }
+}
+
+/// What a key asks the level to do, apart from changing the mode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    None,
+    /// move the player one cell
+    Move(Direction),
+    /// put the level back the way it started
+    Restart,
+}
+
+/// The mode of a run together with what it keeps while playing: keys, lives and the help overlay.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct GameState {
-    event: Option<KeyEvent>,
-    // terminates the entire game
-    terminate: bool,
-    // responsible for game loop
-    run: bool,
-    // draw help overlay
+    mode: Mode,
+    // draw help overlay, on top of whatever mode we are in
     help: bool,
-    // current player position or new point, depending on the context
-    point: Point,
-    // restarts the current level
-    restart: bool,
+    // keys collected in the current level
+    inventory: Inventory,
+    // lives left, None for infinite retries
+    lives: Option<u8>,
 }
 
 impl GameState {
     pub fn new() -> Self {
         GameState {
-            event: None,
-            terminate: false,
-            run: true,
-            restart: false,
+            mode: Mode::Menu,
             help: false,
-            point: Point { x: 0, y: 0 },
+            inventory: Inventory::new(),
+            lives: None,
         }
     }
-    pub fn is_terminate(&self) -> bool {
-        self.terminate
+
+    pub fn mode(&self) -> Mode {
+        self.mode
     }
 
-    pub fn is_run(&self) -> bool {
-        self.run
+    /// The state after the transition, None when `Mode::next` does not allow it from here.
+    pub fn transition(&self, transition: Transition) -> Option<Self> {
+        let mode = self.mode.next(transition)?;
+        Some(match transition {
+            // every level starts without keys and without the help overlay
+            Transition::Start | Transition::Restart | Transition::Respawn => GameState {
+                mode,
+                help: false,
+                inventory: Inventory::new(),
+                lives: self.lives,
+            },
+            _ => GameState { mode, ..*self },
+        })
     }
 
-    pub fn is_help(&self) -> bool {
-        self.help
+    pub fn start(&self) -> Option<Self> {
+        self.transition(Transition::Start)
     }
 
-    pub fn running(&self) -> Self {
-        GameState {
-            event: self.event,
-            terminate: false,
-            run: true,
-            help: false,
-            point: Point { x: 0, y: 0 },
-            restart: self.restart,
-        }
+    pub fn pause(&self) -> Option<Self> {
+        self.transition(Transition::Pause)
     }
 
-    pub fn point(&self) -> Point {
-        self.point
+    pub fn resume(&self) -> Option<Self> {
+        self.transition(Transition::Resume)
     }
 
-    pub fn new_point(&self, point: Point) -> Self {
-        GameState {
-            event: self.event,
-            terminate: self.terminate,
-            run: self.run,
-            point,
-            help: self.help,
-            restart: self.restart,
-        }
+    pub fn restart(&self) -> Option<Self> {
+        self.transition(Transition::Restart)
     }
-    pub fn restart(&self) -> Self {
-        GameState {
-            event: self.event,
-            terminate: self.terminate,
-            run: true,
-            help: self.help,
-            point: self.point,
-            restart: true,
+
+    pub fn die(&self, death: Death) -> Option<Self> {
+        self.transition(Transition::Die(death))
+    }
+
+    pub fn complete(&self) -> Option<Self> {
+        self.transition(Transition::Complete)
+    }
+
+    pub fn quit(&self) -> Option<Self> {
+        self.transition(Transition::Quit)
+    }
+
+    /// Take a life after dying, when we are counting them, and try again or end the run.
+    /// None when the player is not dying.
+    pub fn lose_life(&self) -> Option<Self> {
+        if !matches!(self.mode, Mode::Dying(_)) {
+            return None;
         }
+        let state = GameState {
+            lives: self.lives.map(|lives| lives.saturating_sub(1)),
+            ..*self
+        };
+        match state.lives {
+            Some(0) => state.transition(Transition::Lose),
+            _ => state.transition(Transition::Respawn),
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.mode == Mode::Playing
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.mode == Mode::Paused
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.mode == Mode::GameOver
+    }
+
+    pub fn is_help(&self) -> bool {
+        self.help
     }
 
+    /// Show or hide the help overlay.
     pub fn help(&self) -> Self {
         GameState {
-            event: self.event,
-            terminate: self.terminate,
-            run: self.run,
-            point: self.point,
             help: !self.help,
-            restart: self.restart,
+            ..*self
         }
     }
 
-    pub fn terminate(&self) -> Self {
-        GameState {
-            event: self.event,
-            terminate: true,
-            run: false,
-            point: self.point,
-            help: self.help,
-            restart: false,
-        }
+    pub fn inventory(&self) -> Inventory {
+        self.inventory
     }
 
-    pub fn stop(&self) -> Self {
-        GameState {
-            event: self.event,
-            terminate: self.terminate,
-            run: false,
-            point: self.point,
-            help: self.help,
-            restart: self.restart,
-        }
+    pub fn lives(&self) -> Option<u8> {
+        self.lives
     }
-    pub fn is_restart(&self) -> bool {
-        self.restart
+
+    pub fn new_lives(&self, lives: Option<u8>) -> Self {
+        GameState { lives, ..*self }
     }
 
-    // check if finished
-    pub fn is_finish(&self, finish_position: Option<Point>) -> bool {
-        if let Some(finish) = finish_position {
-            finish.x == self.point.x && finish.y == self.point.y
-        } else {
-            false
-        }
+    pub fn new_inventory(&self, inventory: Inventory) -> Self {
+        GameState { inventory, ..*self }
     }
 
-    // Update movement and global game state
-    pub fn update_player_position(&self, event: KeyEvent) -> GameState {
-        let point = self.point;
-        match event.code {
-            KeyCode::Up => self.new_point(Point {
-                x: point.x,
-                y: point.y - 1,
-            }),
-            KeyCode::Down => self.new_point(Point {
-                x: point.x,
-                y: point.y + 1,
-            }),
-            KeyCode::Left => self.new_point(Point {
-                x: point.x - 1,
-                y: point.y,
-            }),
-            KeyCode::Right => self.new_point(Point {
-                x: point.x + 1,
-                y: point.y,
-            }),
-            KeyCode::Esc => self.terminate(),
-            KeyCode::Char('q') => self.terminate(),
-            KeyCode::Char('r') => self.restart(),
-
-            KeyCode::Char('h') => self.help(),
-            _ => self.new_point(Point {
-                x: point.x,
-                y: point.y,
-            }),
-        }
+    /// Change the mode for the key and say what the level should do.
+    pub fn handle_key(&self, event: KeyEvent) -> (GameState, Command) {
+        let (next, command) = match self.mode {
+            // only unpause and quit are allowed while paused
+            Mode::Paused => match event.code {
+                KeyCode::Char('p') => (self.resume(), Command::None),
+                KeyCode::Esc | KeyCode::Char('q') => (self.quit(), Command::None),
+                _ => (Some(*self), Command::None),
+            },
+            Mode::Playing => match event.code {
+                KeyCode::Up => (Some(*self), Command::Move(Direction::Up)),
+                KeyCode::Down => (Some(*self), Command::Move(Direction::Down)),
+                KeyCode::Left => (Some(*self), Command::Move(Direction::Left)),
+                KeyCode::Right => (Some(*self), Command::Move(Direction::Right)),
+                KeyCode::Esc | KeyCode::Char('q') => (self.quit(), Command::None),
+                KeyCode::Char('r') => (self.restart(), Command::Restart),
+                KeyCode::Char('h') => (Some(self.help()), Command::None),
+                KeyCode::Char('p') => (self.pause(), Command::None),
+                _ => (Some(*self), Command::None),
+            },
+            _ => (Some(*self), Command::None),
+        };
+        // every transition above is allowed from the mode it is matched on
+        debug_assert!(next.is_some(), "no key transition from {:?}", self.mode);
+        (next.unwrap_or(*self), command)
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        GameState::new()
     }
 }
 #[cfg(test)]
 mod tests {
 
+    use super::Command;
     use super::GameState;
-    use super::Point;
+    use super::Inventory;
+    use super::Mode;
+    use super::Transition;
+    use crate::utils::death::Death;
+    use crate::utils::direction::Direction;
+    use crate::utils::key_color::KeyColor;
     use crossterm::event::KeyCode;
     use crossterm::event::KeyEvent;
     use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
     #[test]
     fn new() {
         let game_state = GameState::new();
-        assert_eq!(game_state.point.x, 0);
-        assert_eq!(game_state.point.y, 0);
-        assert_eq!(game_state.event, None);
-        assert!(game_state.is_run());
+        assert_eq!(game_state.mode(), Mode::Menu);
         assert!(!game_state.is_help());
-        assert!(!game_state.is_restart());
-        assert!(!game_state.is_terminate());
-        assert!(game_state.is_finish(Some(Point { x: 0, y: 0 })));
-        assert!(!game_state.is_finish(None));
+        assert!(game_state.inventory().is_empty());
+        assert_eq!(game_state.lives(), None);
+        assert!(!game_state.is_game_over());
+        assert!(game_state.start().unwrap().is_playing());
     }
-    #[test]
-    fn move_up() {
-        let event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
-        let game_state = GameState::new();
 
-        let new_state = game_state.update_player_position(event);
-        assert_eq!(new_state.point.x, 0);
-        assert_eq!(new_state.point.y, -1);
-    }
     #[test]
-    fn move_right() {
-        let event = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
-        let game_state = GameState::new();
-
-        let new_state = game_state.update_player_position(event);
-        assert_eq!(new_state.point.x, 1);
-        assert_eq!(new_state.point.y, 0);
+    fn allowed_transitions() {
+        let death = Death::Void;
+        let allowed = [
+            (Mode::Menu, Transition::Start, Mode::Playing),
+            (Mode::LevelComplete, Transition::Start, Mode::Playing),
+            (Mode::Playing, Transition::Pause, Mode::Paused),
+            (Mode::Paused, Transition::Resume, Mode::Playing),
+            (Mode::Playing, Transition::Restart, Mode::Playing),
+            (Mode::Playing, Transition::Die(death), Mode::Dying(death)),
+            (Mode::Dying(death), Transition::Respawn, Mode::Playing),
+            (Mode::Dying(death), Transition::Lose, Mode::GameOver),
+            (Mode::Playing, Transition::Complete, Mode::LevelComplete),
+            (Mode::Playing, Transition::Quit, Mode::Menu),
+            (Mode::Paused, Transition::Quit, Mode::Menu),
+        ];
+        for (from, transition, to) in allowed {
+            assert_eq!(from.next(transition), Some(to));
+        }
     }
-    #[test]
-    fn move_left() {
-        let event = KeyEvent::new(KeyCode::Left, KeyModifiers::NONE);
-        let game_state = GameState::new();
 
-        let new_state = game_state.update_player_position(event);
-        assert_eq!(new_state.point.x, -1);
-        assert_eq!(new_state.point.y, 0);
-    }
     #[test]
-    fn move_down() {
-        let event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
-        let game_state = GameState::new();
-
-        let new_state = game_state.update_player_position(event);
-        assert_eq!(new_state.point.x, 0);
-        assert_eq!(new_state.point.y, 1);
+    fn invalid_transitions() {
+        assert_eq!(Mode::Playing.next(Transition::Start), None);
+        assert_eq!(Mode::Paused.next(Transition::Pause), None);
+        assert_eq!(Mode::Paused.next(Transition::Die(Death::Enemy)), None);
+        assert_eq!(Mode::Paused.next(Transition::Complete), None);
+        assert_eq!(Mode::GameOver.next(Transition::Start), None);
+        assert_eq!(Mode::GameOver.next(Transition::Respawn), None);
+        assert_eq!(Mode::Menu.next(Transition::Pause), None);
+        assert_eq!(Mode::LevelComplete.next(Transition::Die(Death::Void)), None);
+        // and the state has none of them
+        let game_state = GameState::new().start().unwrap().pause().unwrap();
+        assert_eq!(game_state.complete(), None);
+        assert_eq!(game_state.lose_life(), None);
+        assert_eq!(game_state.start(), None);
     }
+
     #[test]
-    fn escape_game() {
-        let event = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
-        let game_state = GameState::new();
+    fn lose_life() {
+        let game_state = GameState::new().new_lives(Some(2)).start().unwrap();
+        let new_state = game_state.die(Death::Enemy).unwrap();
+        assert_eq!(new_state.mode(), Mode::Dying(Death::Enemy));
+        let new_state = new_state.lose_life().unwrap();
+        assert_eq!(new_state.lives(), Some(1));
+        assert!(new_state.is_playing());
+        let new_state = new_state.die(Death::Void).unwrap().lose_life().unwrap();
+        assert_eq!(new_state.lives(), Some(0));
+        assert!(new_state.is_game_over());
 
-        let new_state = game_state.update_player_position(event);
-        assert!(new_state.terminate);
+        // lives survive restarting the level and the next level
+        assert_eq!(game_state.restart().unwrap().lives(), Some(2));
+        assert_eq!(
+            game_state.complete().unwrap().start().unwrap().lives(),
+            Some(2)
+        );
     }
 
     #[test]
-    fn q_game() {
-        let event = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
-        let game_state = GameState::new();
+    fn lose_life_casual() {
+        let new_state = GameState::new()
+            .start()
+            .unwrap()
+            .die(Death::Void)
+            .unwrap()
+            .lose_life()
+            .unwrap();
+        assert_eq!(new_state.lives(), None);
+        assert!(new_state.is_playing());
+    }
 
-        let new_state = game_state.update_player_position(event);
-        assert!(new_state.terminate);
+    #[test]
+    fn moves() {
+        let game_state = GameState::new().start().unwrap();
+        for (code, direction) in [
+            (KeyCode::Up, Direction::Up),
+            (KeyCode::Down, Direction::Down),
+            (KeyCode::Left, Direction::Left),
+            (KeyCode::Right, Direction::Right),
+        ] {
+            let (new_state, command) = game_state.handle_key(key(code));
+            assert_eq!(new_state, game_state);
+            assert_eq!(command, Command::Move(direction));
+        }
     }
+
     #[test]
-    fn restart_game() {
-        let event = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
-        let game_state = GameState::new();
+    fn quit() {
+        for code in [KeyCode::Esc, KeyCode::Char('q')] {
+            let (new_state, command) = GameState::new().start().unwrap().handle_key(key(code));
+            assert_eq!(new_state.mode(), Mode::Menu);
+            assert_eq!(command, Command::None);
+        }
+    }
 
-        let new_state = game_state.update_player_position(event);
-        assert!(new_state.is_restart());
+    #[test]
+    fn restart() {
+        let mut inventory = Inventory::new();
+        inventory.add(KeyColor::Green);
+        let game_state = GameState::new()
+            .start()
+            .unwrap()
+            .new_inventory(inventory)
+            .help();
+        let (new_state, command) = game_state.handle_key(key(KeyCode::Char('r')));
+        assert_eq!(command, Command::Restart);
+        assert!(new_state.is_playing());
+        assert!(new_state.inventory().is_empty());
+        assert!(!new_state.is_help());
     }
+
     #[test]
     fn help() {
-        let event = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE);
-        let game_state = GameState::new();
-
-        let new_state = game_state.update_player_position(event);
+        let (new_state, command) = GameState::new()
+            .start()
+            .unwrap()
+            .handle_key(key(KeyCode::Char('h')));
         assert!(new_state.is_help());
+        assert!(new_state.is_playing());
+        assert_eq!(command, Command::None);
     }
-    #[test]
-    fn any_unbound_key() {
-        let event = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
-        let game_state = GameState::new();
 
-        let new_state = game_state.update_player_position(event);
-        assert_eq!(new_state.is_help(), game_state.is_help());
-        assert_eq!(new_state.is_restart(), game_state.is_restart());
-        assert_eq!(new_state.is_terminate(), game_state.is_terminate());
-        assert_eq!(new_state.is_run(), game_state.is_run());
-    }
     #[test]
-    fn to_running_state() {
-        let game_state = GameState::new();
-        let new_state = game_state.running();
-        assert_eq!(new_state.event, game_state.event);
-        assert!(!new_state.terminate);
-        assert!(new_state.run);
-        assert!(!new_state.help);
-        assert_eq!(new_state.point, Point { x: 0, y: 0 });
-        assert_eq!(new_state.restart, game_state.restart);
+    fn any_unbound_key() {
+        let game_state = GameState::new().start().unwrap();
+        let (new_state, command) = game_state.handle_key(key(KeyCode::Char('a')));
+        assert_eq!(new_state, game_state);
+        assert_eq!(command, Command::None);
     }
 
     #[test]
-    fn to_restart_state() {
-        let game_state = GameState::new();
-        let new_state = game_state.restart();
-        assert_eq!(new_state.event, game_state.event);
-        assert_eq!(new_state.terminate, game_state.terminate);
-        assert!(new_state.run);
-        assert_eq!(new_state.help, game_state.help);
-        assert_eq!(new_state.point, game_state.point);
-        assert!(new_state.restart);
+    fn pause() {
+        let game_state = GameState::new().start().unwrap();
+        let (new_state, _) = game_state.handle_key(key(KeyCode::Char('p')));
+        assert!(new_state.is_paused());
+        let (new_state, _) = new_state.handle_key(key(KeyCode::Char('p')));
+        assert!(new_state.is_playing());
     }
+
     #[test]
-    fn to_help_state() {
-        let game_state = GameState::new();
-        let new_state = game_state.help();
-        assert_eq!(new_state.event, game_state.event);
-        assert_eq!(new_state.terminate, game_state.terminate);
-        assert_eq!(new_state.run, game_state.run);
-        assert_eq!(!new_state.help, game_state.help);
-        assert_eq!(new_state.point, game_state.point);
-        assert_eq!(new_state.restart, game_state.restart);
+    fn paused_ignores_input() {
+        let game_state = GameState::new().start().unwrap().pause().unwrap();
+        for code in [KeyCode::Up, KeyCode::Char('r'), KeyCode::Char('h')] {
+            let (new_state, command) = game_state.handle_key(key(code));
+            assert_eq!(new_state, game_state);
+            assert_eq!(command, Command::None);
+        }
+
+        // but quitting still works
+        let (new_state, _) = game_state.handle_key(key(KeyCode::Char('q')));
+        assert_eq!(new_state.mode(), Mode::Menu);
     }
 
     #[test]
-    fn to_terminate_state() {
-        let game_state = GameState::new();
-        let new_state = game_state.terminate();
-        assert_eq!(new_state.event, game_state.event);
-        assert!(new_state.terminate);
-        assert!(!new_state.run);
-        assert_eq!(new_state.help, game_state.help);
-        assert_eq!(new_state.point, game_state.point);
-        assert!(!new_state.restart);
+    fn keys_outside_of_a_level() {
+        for game_state in [
+            GameState::new(),
+            GameState::new().start().unwrap().complete().unwrap(),
+            GameState::new()
+                .new_lives(Some(1))
+                .start()
+                .unwrap()
+                .die(Death::Enemy)
+                .unwrap()
+                .lose_life()
+                .unwrap(),
+        ] {
+            let (new_state, command) = game_state.handle_key(key(KeyCode::Char('p')));
+            assert_eq!(new_state, game_state);
+            assert_eq!(command, Command::None);
+        }
     }
 
     #[test]
-    fn to_stop_state() {
-        let game_state = GameState::new();
-        let new_state = game_state.stop();
-        assert_eq!(new_state.event, game_state.event);
-        assert_eq!(new_state.terminate, game_state.terminate);
-        assert!(!new_state.run);
-        assert_eq!(new_state.help, game_state.help);
-        assert_eq!(new_state.point, game_state.point);
-        assert_eq!(new_state.restart, game_state.restart);
+    fn to_next_level() {
+        let mut inventory = Inventory::new();
+        inventory.add(KeyColor::Green);
+        let game_state = GameState::new()
+            .start()
+            .unwrap()
+            .new_inventory(inventory)
+            .help();
+        // keys survive toggling help
+        assert_eq!(game_state.help().inventory(), inventory);
+
+        let new_state = game_state.complete().unwrap();
+        assert_eq!(new_state.mode(), Mode::LevelComplete);
+        let new_state = new_state.start().unwrap();
+        assert!(new_state.is_playing());
+        assert!(new_state.inventory().is_empty());
+        assert!(!new_state.is_help());
     }
 }
@@ -0,0 +1,289 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::inventory::Inventory;
+use crate::utils::level::Level;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+use crate::utils::solver::solve;
+use crate::utils::solver::Solution;
+use crate::utils::solver::MAX_STATES;
+
+// Layouts tried for one seed before giving up.
+const MAX_ATTEMPTS: u32 = 100;
+
+/// Settings for a generated level.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GeneratorParams {
+    pub width: u8,
+    pub height: u8,
+    pub enemies: u8,
+    pub door_switch_pairs: u8,
+    /// chance in percent for every free cell to become void
+    pub void_density: u8,
+    pub teleporters: u8,
+}
+
+impl GeneratorParams {
+    /// Levels of an endless run get bigger and harder every few rounds.
+    pub fn for_round(round: usize) -> Self {
+        let round = round.min(30) as u8;
+        GeneratorParams {
+            width: 8 + round / 2,
+            height: 5 + round / 4,
+            enemies: (round / 2).min(6),
+            door_switch_pairs: (round / 3).min(3),
+            void_density: 10 + round / 2,
+            teleporters: (round / 4).min(2),
+        }
+    }
+}
+
+/// Small deterministic random number generator (splitmix64),
+/// so a seed gives the same level on every platform.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in 0..max
+    pub fn below(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+fn layout(random: &mut Random, params: &GeneratorParams) -> Level {
+    let mut level = Level::empty(params.width, params.height);
+    let mut free: Vec<Point> = (0..params.height as i8)
+        .flat_map(|y| (0..params.width as i8).map(move |x| Point { x, y }))
+        .collect();
+    random.shuffle(&mut free);
+
+    level.update(free.pop().unwrap(), Cell::Player(Powerup::None));
+    level.update(free.pop().unwrap(), Cell::Exit);
+    for id in 0..params.door_switch_pairs {
+        if free.len() < 2 {
+            break;
+        }
+        level.update(free.pop().unwrap(), Cell::Door(id));
+        level.update(free.pop().unwrap(), Cell::Switch(id));
+    }
+    for _ in 0..params.teleporters {
+        if free.len() < 2 {
+            break;
+        }
+        let teleporter = free.pop().unwrap();
+        // the destination stays empty, so the player can land on it
+        let destination = free[random.below(free.len())];
+        free.retain(|point| *point != destination);
+        level.update(teleporter, Cell::OneWayTeleporter(destination));
+    }
+    let directions = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Right,
+        Direction::Left,
+    ];
+    for _ in 0..params.enemies {
+        if let Some(point) = free.pop() {
+            let direction = directions[random.below(directions.len())];
+            level.update(point, Cell::CounterClockwiseEnemy(direction));
+        }
+    }
+    for point in free {
+        if random.below(100) < params.void_density as usize {
+            level.update(point, Cell::Void);
+        }
+    }
+    level
+}
+
+/// Fewest moves a generated level takes, half of its width and height, at least 1.
+pub fn min_moves(params: &GeneratorParams) -> usize {
+    ((params.width as usize + params.height as usize) / 2).max(1)
+}
+
+fn too_small(params: &GeneratorParams) -> bool {
+    (params.width as usize) * (params.height as usize) < 2
+}
+
+/// Build a level for the given seed. Only layouts the solver finishes in at least
+/// [`min_moves`] are kept, the ones it can't solve within its budget are thrown away as well.
+/// Returns None when the level is too small or no such layout was found.
+pub fn generate(seed: u64, params: &GeneratorParams) -> Option<Level> {
+    if too_small(params) {
+        return None;
+    }
+    let mut random = Random::new(seed);
+    (0..MAX_ATTEMPTS)
+        .map(|_| layout(&mut random, params))
+        .find(|level| {
+            matches!(
+                solve(level, Inventory::new(), MAX_STATES),
+                Solution::Path(moves) if moves.len() >= min_moves(params)
+            )
+        })
+}
+
+/// Like [`generate`], but when no layout of the seed works the seeds derived from it are tried
+/// one after another until a level comes back, so runs never end early or come out shorter.
+/// Returns None only when the level is too small.
+pub fn generate_retrying(seed: u64, params: &GeneratorParams) -> Option<Level> {
+    if too_small(params) {
+        return None;
+    }
+    // the first try is the seed itself
+    (0..).find_map(|retry: u64| generate(seed ^ retry.wrapping_mul(0x9e37_79b9_7f4a_7c15), params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate;
+    use super::generate_retrying;
+    use super::min_moves;
+    use super::GeneratorParams;
+    use super::Random;
+    use crate::utils::cell::Cell;
+    use crate::utils::inventory::Inventory;
+    use crate::utils::level::Level;
+    use crate::utils::point::Point;
+    use crate::utils::solver::solve;
+    use crate::utils::solver::Solution;
+    use crate::utils::solver::MAX_STATES;
+
+    fn params() -> GeneratorParams {
+        GeneratorParams {
+            width: 10,
+            height: 6,
+            enemies: 2,
+            door_switch_pairs: 2,
+            void_density: 30,
+            teleporters: 1,
+        }
+    }
+
+    fn count(level: &Level, matches: fn(&Cell) -> bool) -> usize {
+        level.data.values().filter(|cell| matches(cell)).count()
+    }
+
+    #[test]
+    fn random_is_deterministic() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+        assert!((0..100).all(|_| a.below(7) < 7));
+    }
+
+    #[test]
+    fn same_seed_same_level() {
+        let a = generate(7, &params()).unwrap();
+        let b = generate(7, &params()).unwrap();
+        assert!(a.data == b.data);
+    }
+
+    #[test]
+    fn uses_params() {
+        let level = generate(3, &params()).unwrap();
+        assert_eq!(level.size(), (9, 5));
+        assert!(level.player_position().is_some());
+        assert!(level.finish_position().is_some());
+        assert_eq!(count(&level, |cell| matches!(cell, Cell::Door(_))), 2);
+        assert_eq!(count(&level, |cell| matches!(cell, Cell::Switch(_))), 2);
+        assert_eq!(
+            count(&level, |cell| matches!(cell, Cell::OneWayTeleporter(_))),
+            1
+        );
+        assert_eq!(
+            count(&level, |cell| matches!(
+                cell,
+                Cell::CounterClockwiseEnemy(_)
+            )),
+            2
+        );
+    }
+
+    #[test]
+    fn levels_are_solvable() {
+        // every level that comes back has a finishing path, in every round
+        for round in [0, 6, 12] {
+            let params = GeneratorParams::for_round(round);
+            for seed in 0..10 {
+                if let Some(level) = generate(seed, &params) {
+                    match solve(&level, Inventory::new(), MAX_STATES) {
+                        Solution::Path(moves) => assert!(moves.len() >= min_moves(&params)),
+                        solution => panic!("round {} seed {}: {:?}", round, seed, solution),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn retrying_seeds() {
+        // the seed itself comes first
+        assert!(
+            generate_retrying(7, &params()).unwrap().data == generate(7, &params()).unwrap().data
+        );
+        // none of the seeds of a level too small for a player and an exit is tried
+        let tiny = GeneratorParams {
+            width: 1,
+            height: 1,
+            ..params()
+        };
+        assert!(generate_retrying(0, &tiny).is_none());
+    }
+
+    #[test]
+    fn fixed_seed() {
+        // the daily challenge relies on every platform generating the same levels
+        let level = generate(7, &params()).unwrap();
+        assert_eq!(level.player_position(), Some(Point { x: 2, y: 3 }));
+        assert_eq!(level.finish_position(), Some(Point { x: 9, y: 1 }));
+        match solve(&level, Inventory::new(), MAX_STATES) {
+            Solution::Path(moves) => assert_eq!(moves.len(), 9),
+            solution => panic!("{:?}", solution),
+        }
+    }
+
+    #[test]
+    fn too_small() {
+        let tiny = GeneratorParams {
+            width: 1,
+            height: 1,
+            ..params()
+        };
+        assert!(generate(0, &tiny).is_none());
+    }
+
+    #[test]
+    fn rounds_get_harder() {
+        let first = GeneratorParams::for_round(0);
+        let later = GeneratorParams::for_round(10);
+        assert!(later.width > first.width);
+        assert!(later.enemies > first.enemies);
+        assert_eq!(
+            GeneratorParams::for_round(100),
+            GeneratorParams::for_round(30)
+        );
+    }
+}
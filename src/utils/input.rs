@@ -0,0 +1,173 @@
+use crate::utils::clock::ManualClock;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use crossterm::Result;
+use std::collections::VecDeque;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// Where the game gets its keys, mouse clicks and focus changes from.
+pub trait InputSource {
+    /// The next event, None when nothing happened within the timeout.
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>>;
+
+    /// Wait for the next event.
+    fn read_event(&mut self) -> Result<Event>;
+}
+
+/// Events of the terminal, read with crossterm.
+pub struct TerminalInput;
+
+impl InputSource for TerminalInput {
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        if crossterm::event::poll(timeout)? {
+            crossterm::event::read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read_event(&mut self) -> Result<Event> {
+        crossterm::event::read()
+    }
+}
+
+/// Replays a queue of events, one for every poll or read, to drive the game from tests.
+/// Running out of events is an error, so a script that never quits can't hang the game.
+pub struct ScriptedInput {
+    events: VecDeque<Option<Event>>,
+    // moved forward by the timeout of every idle poll, like waiting for the terminal would
+    clock: Option<ManualClock>,
+}
+
+impl ScriptedInput {
+    pub fn new() -> Self {
+        ScriptedInput {
+            events: VecDeque::new(),
+            clock: None,
+        }
+    }
+
+    /// Every idle poll moves the clock forward by its timeout.
+    pub fn with_clock(self, clock: ManualClock) -> Self {
+        ScriptedInput {
+            clock: Some(clock),
+            ..self
+        }
+    }
+
+    pub fn event(mut self, event: Event) -> Self {
+        self.events.push_back(Some(event));
+        self
+    }
+
+    pub fn key(self, code: KeyCode) -> Self {
+        self.event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    pub fn keys(self, codes: impl IntoIterator<Item = KeyCode>) -> Self {
+        codes.into_iter().fold(self, |input, code| input.key(code))
+    }
+
+    /// A poll that times out without any event, reading skips it.
+    pub fn idle(mut self) -> Self {
+        self.events.push_back(None);
+        self
+    }
+
+    pub fn idles(self, count: usize) -> Self {
+        (0..count).fold(self, |input, _| input.idle())
+    }
+
+    /// Events not replayed yet, idle polls included.
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+
+    fn next(&mut self) -> Result<Option<Event>> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "no scripted events left"))
+    }
+}
+
+impl Default for ScriptedInput {
+    fn default() -> Self {
+        ScriptedInput::new()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let event = self.next()?;
+        if let (None, Some(clock)) = (&event, &self.clock) {
+            clock.advance(timeout);
+        }
+        Ok(event)
+    }
+
+    fn read_event(&mut self) -> Result<Event> {
+        loop {
+            if let Some(event) = self.next()? {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputSource;
+    use super::ScriptedInput;
+    use crate::utils::clock::Clock;
+    use crate::utils::clock::ManualClock;
+    use crossterm::event::Event;
+    use crossterm::event::KeyCode;
+    use crossterm::event::KeyEvent;
+    use crossterm::event::KeyModifiers;
+    use std::time::Duration;
+
+    #[test]
+    fn scripted_input() {
+        let mut input = ScriptedInput::new()
+            .key(KeyCode::Up)
+            .idle()
+            .keys([KeyCode::Char('q'), KeyCode::Esc]);
+        assert_eq!(input.remaining(), 4);
+        let timeout = Duration::from_millis(100);
+        assert_eq!(
+            input.poll_event(timeout).unwrap(),
+            Some(Event::Key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)))
+        );
+        assert_eq!(input.poll_event(timeout).unwrap(), None);
+        assert_eq!(
+            input.read_event().unwrap(),
+            Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE))
+        );
+        // reading skips idle polls
+        let mut input = ScriptedInput::new().idle().key(KeyCode::Esc);
+        assert_eq!(
+            input.read_event().unwrap(),
+            Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        );
+        assert!(input.read_event().is_err());
+        assert!(input.poll_event(timeout).is_err());
+    }
+
+    #[test]
+    fn idle_polls_take_time() {
+        let clock = ManualClock::new();
+        let mut input = ScriptedInput::new()
+            .with_clock(clock.clone())
+            .idles(2)
+            .key(KeyCode::Up);
+        let timeout = Duration::from_millis(100);
+        assert_eq!(input.poll_event(timeout).unwrap(), None);
+        assert_eq!(input.poll_event(timeout).unwrap(), None);
+        assert!(input.poll_event(timeout).unwrap().is_some());
+        assert_eq!(clock.now(), Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,71 @@
+use super::key_color::KeyColor;
+
+/// Keys carried by the player. Every key opens exactly one locked door of its color.
+#[derive(Clone, PartialEq, Copy, Eq, Hash, Debug)]
+pub struct Inventory {
+    keys: [u8; 4],
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Inventory { keys: [0; 4] }
+    }
+
+    pub fn add(&mut self, color: KeyColor) {
+        self.keys[color as usize] += 1;
+    }
+
+    pub fn count(&self, color: KeyColor) -> u8 {
+        self.keys[color as usize]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.iter().all(|&count| count == 0)
+    }
+
+    /// use up a key of the given color, if we carry one
+    pub fn take(&mut self, color: KeyColor) -> bool {
+        if self.count(color) > 0 {
+            self.keys[color as usize] -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Inventory;
+    use super::KeyColor;
+    #[test]
+    fn new() {
+        let inventory = Inventory::new();
+        assert!(inventory.is_empty());
+        for color in KeyColor::all() {
+            assert_eq!(inventory.count(color), 0);
+        }
+    }
+
+    #[test]
+    fn add_and_take() {
+        let mut inventory = Inventory::new();
+        inventory.add(KeyColor::Blue);
+        inventory.add(KeyColor::Blue);
+        assert!(!inventory.is_empty());
+        assert_eq!(inventory.count(KeyColor::Blue), 2);
+        assert_eq!(inventory.count(KeyColor::Red), 0);
+
+        assert!(inventory.take(KeyColor::Blue));
+        assert!(inventory.take(KeyColor::Blue));
+        assert!(!inventory.take(KeyColor::Blue));
+        assert!(!inventory.take(KeyColor::Red));
+        assert!(inventory.is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+/// Color of a key and the locked doors it opens.
+#[derive(Clone, PartialEq, Copy, Eq, Hash, Debug)]
+pub enum KeyColor {
+    Red,
+    Green,
+    Blue,
+    Yellow,
+}
+
+impl KeyColor {
+    pub fn all() -> [KeyColor; 4] {
+        [
+            KeyColor::Red,
+            KeyColor::Green,
+            KeyColor::Blue,
+            KeyColor::Yellow,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyColor::Red => "red",
+            KeyColor::Green => "green",
+            KeyColor::Blue => "blue",
+            KeyColor::Yellow => "yellow",
+        }
+    }
+}
@@ -1,14 +1,73 @@
 use super::cell::Cell;
+use super::death::Death;
 use super::direction::Direction;
+use super::inventory::Inventory;
+use super::level_info::LevelInfo;
 use super::point::Point;
 use super::powerup::Powerup;
+use super::trigger::Action;
+use super::trigger::Trigger;
+use super::trigger::TriggerEvent;
 use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// The point one step from `point` in the direction.
+pub fn neighbour(point: Point, direction: Direction) -> Point {
+    match direction {
+        Direction::Up => Point {
+            x: point.x,
+            y: point.y - 1,
+        },
+        Direction::Down => Point {
+            x: point.x,
+            y: point.y + 1,
+        },
+        Direction::Right => Point {
+            x: point.x + 1,
+            y: point.y,
+        },
+        Direction::Left => Point {
+            x: point.x - 1,
+            y: point.y,
+        },
+    }
+}
+
+/// The map of a level with everything going on in it.
+///
+/// Levels are built with [`Level::empty`] and [`Level::update`], and played with
+/// [`Level::move_player`], [`Level::update_enemies`] and [`Level::update_conveyors`].
 #[derive(Clone)]
 pub struct Level {
     pub(crate) data: HashMap<Point, Cell>,
+    // terrain hidden below a player, enemy or box standing on it
+    pub(crate) floor: HashMap<Point, Cell>,
+    // how the player was removed from the map
+    death: Option<Death>,
+    // keys and powerups the player collected
+    pickups: u32,
+    // how far the player can see, None shows the whole level
+    visibility: Option<u8>,
+    // cells the player has seen as they were last seen, drawn from memory when out of sight
+    explored: HashMap<Point, Cell>,
+    // name, intro, par values and everything else not on the map
+    info: LevelInfo,
+    // scripted actions waiting for their event, fired ones are removed
+    triggers: Vec<Trigger>,
+    // events of the current move, handled by run_triggers once the move is done
+    events: Vec<TriggerEvent>,
+    // running timers as (id, enemy ticks left)
+    timers: Vec<(u8, u32)>,
+    // texts of the sign cells
+    signs: HashMap<Point, String>,
+    // last message shown by a trigger or sign
+    message: Option<String>,
+    // whether the message is the text of a sign, it goes away when walking away from the sign
+    reading: bool,
 }
 
 impl Level {
+    /// A level of the given size filled with `Cell::Empty`.
     pub fn empty(width: u8, height: u8) -> Self {
         let mut data = HashMap::new();
 
@@ -24,29 +83,30 @@ impl Level {
             }
         }
 
-        Self { data }
+        Self {
+            data,
+            floor: HashMap::new(),
+            death: None,
+            pickups: 0,
+            visibility: None,
+            explored: HashMap::new(),
+            info: LevelInfo::default(),
+            triggers: vec![],
+            events: vec![],
+            timers: vec![],
+            signs: HashMap::new(),
+            message: None,
+            reading: false,
+        }
     }
 
+    /// Moves every enemy one step, an enemy that is blocked turns counter clockwise instead.
+    /// Also counts down the timers and runs the triggers of what happened.
     pub fn update_enemies(&mut self) {
         for (&point, &cell) in self.data.clone().iter() {
             // try move
             let optional_target_point = match cell {
-                Cell::CounterClockwiseEnemy(Direction::Right) => Some(Point {
-                    x: point.x + 1,
-                    y: point.y,
-                }),
-                Cell::CounterClockwiseEnemy(Direction::Up) => Some(Point {
-                    x: point.x,
-                    y: point.y - 1,
-                }),
-                Cell::CounterClockwiseEnemy(Direction::Down) => Some(Point {
-                    x: point.x,
-                    y: point.y + 1,
-                }),
-                Cell::CounterClockwiseEnemy(Direction::Left) => Some(Point {
-                    x: point.x - 1,
-                    y: point.y,
-                }),
+                Cell::CounterClockwiseEnemy(direction) => Some(neighbour(point, direction)),
                 _ => None,
             };
             let target_rotation = match cell {
@@ -56,20 +116,10 @@ impl Level {
                 _ => Direction::Up,
             };
             if let Some(target_point) = optional_target_point {
-                let cloned_cell = &self.data.get(&target_point).cloned();
-                match cloned_cell {
-                    // we can move
-                    Some(Cell::Empty) => {
-                        self.update(point, Cell::Empty);
-                        self.update(target_point, cell);
-                    }
-                    // remove player from grid
-                    Some(Cell::Player(Powerup::None)) => {
-                        self.update(point, Cell::Empty);
-                        self.update(target_point, cell)
-                    }
-                    // player will eat us
-                    Some(Cell::Player(Powerup::Invincible(_))) => self.update(point, Cell::Empty),
+                if self.enemy_enters(point, target_point, cell) {
+                    continue;
+                }
+                match self.data.get(&target_point) {
                     // rotate enemy for the following cases without moving it.
                     Some(Cell::VerticalWall) => {
                         self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
@@ -86,6 +136,21 @@ impl Level {
                     Some(Cell::Switch(_)) => {
                         self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
                     }
+                    Some(Cell::ToggleSwitch(_)) => {
+                        self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
+                    }
+                    Some(Cell::Key(_)) => {
+                        self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
+                    }
+                    Some(Cell::LockedDoor(_)) => {
+                        self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
+                    }
+                    Some(Cell::Box) => {
+                        self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
+                    }
+                    Some(Cell::Sign) => {
+                        self.update(point, Cell::CounterClockwiseEnemy(target_rotation));
+                    }
                     // collision with something else not implemented.
                     // It would require data structure change to have two elements on the same
                     // cell.
@@ -95,21 +160,467 @@ impl Level {
                 }
             }
         }
+        self.update_timers();
+        self.run_triggers();
+    }
+
+    // An enemy moving from `point` onto `target`, on its own or carried by a conveyor.
+    // It catches a player without powerup and is eaten by an invincible one.
+    // Returns false when the target blocks the enemy.
+    fn enemy_enters(&mut self, point: Point, target: Point, enemy: Cell) -> bool {
+        match self.data.get(&target) {
+            // we can move
+            Some(Cell::Empty)
+            | Some(Cell::PressurePlate(_))
+            | Some(Cell::OpenDoor(_))
+            | Some(Cell::Ice)
+            | Some(Cell::Conveyor(_)) => {}
+            // remove player from grid
+            Some(Cell::Player(Powerup::None)) => self.death = Some(Death::Enemy),
+            // player will eat us
+            Some(Cell::Player(Powerup::Invincible(_))) => {
+                self.vacate(point);
+                return true;
+            }
+            _ => return false,
+        }
+        self.occupy(target, enemy);
+        self.vacate(point);
+        self.emit(TriggerEvent::EnemyEntered(target));
+        true
+    }
+
+    // Count down the running timers by one enemy tick.
+    fn update_timers(&mut self) {
+        for (_, ticks) in self.timers.iter_mut() {
+            *ticks = ticks.saturating_sub(1);
+        }
+        let mut expired: Vec<u8> = self
+            .timers
+            .iter()
+            .filter(|(_, ticks)| *ticks == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        expired.sort();
+        self.timers.retain(|(_, ticks)| *ticks > 0);
+        for id in expired {
+            self.emit(TriggerEvent::TimerExpired(id));
+        }
+    }
+
+    /// Adds scripted actions that run when their event happens.
+    pub fn add_trigger(&mut self, trigger: Trigger) {
+        self.triggers.push(trigger);
+    }
+
+    /// Triggers that did not fire yet, and the repeating ones.
+    pub fn triggers(&self) -> &[Trigger] {
+        &self.triggers
+    }
+
+    /// Last text shown by a trigger or a sign.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Puts a sign with its text on the point.
+    pub fn add_sign(&mut self, point: Point, text: &str) {
+        self.update(point, Cell::Sign);
+        self.signs.insert(point, String::from(text));
+    }
+
+    /// Text of the sign on the point.
+    pub fn sign(&self, point: Point) -> Option<&str> {
+        self.signs.get(&point).map(String::as_str)
+    }
+
+    // Show the text of a sign the player stands on or next to, and stop showing it once the
+    // player walked away.
+    fn read_signs(&mut self) {
+        let player = match self.player_position() {
+            Some(player) => player,
+            None => return,
+        };
+        let nearby = [
+            player,
+            neighbour(player, Direction::Up),
+            neighbour(player, Direction::Down),
+            neighbour(player, Direction::Left),
+            neighbour(player, Direction::Right),
+        ];
+        match nearby.iter().find_map(|&point| self.sign(point)) {
+            Some(text) => {
+                self.message = Some(String::from(text));
+                self.reading = true;
+            }
+            None if self.reading => {
+                self.message = None;
+                self.reading = false;
+            }
+            None => {}
+        }
+    }
+
+    fn emit(&mut self, event: TriggerEvent) {
+        self.events.push(event);
+    }
+
+    // Run the actions of every trigger waiting for an event that happened since the last call.
+    fn run_triggers(&mut self) {
+        for event in std::mem::take(&mut self.events) {
+            let mut actions = vec![];
+            for trigger in self
+                .triggers
+                .iter()
+                .filter(|trigger| trigger.event == event)
+            {
+                actions.extend(trigger.actions.iter().cloned());
+            }
+            self.triggers
+                .retain(|trigger| !(trigger.once && trigger.event == event));
+            for action in actions {
+                self.run_action(action);
+            }
+        }
+    }
+
+    fn run_action(&mut self, action: Action) {
+        match action {
+            Action::OpenDoors(door_id) => self.set_doors(door_id, true),
+            Action::CloseDoors(door_id) => self.set_doors(door_id, false),
+            Action::SpawnEnemy(point, direction) => match self.data.get(&point) {
+                Some(Cell::Empty)
+                | Some(Cell::PressurePlate(_))
+                | Some(Cell::OpenDoor(_))
+                | Some(Cell::Ice)
+                | Some(Cell::Conveyor(_)) => {
+                    self.occupy(point, Cell::CounterClockwiseEnemy(direction))
+                }
+                _ => {}
+            },
+            Action::ShowMessage(message) => {
+                self.message = Some(message);
+                self.reading = false;
+            }
+            Action::StartTimer { id, ticks } => {
+                self.timers.retain(|(timer_id, _)| *timer_id != id);
+                self.timers.push((id, ticks));
+            }
+        }
     }
 
+    /// Replaces the cell on the point, this is how levels are built.
     pub fn update(&mut self, point: Point, cell: Cell) {
         self.data.insert(point, cell);
     }
-    pub fn door_position(&self, switch_id: u8) -> Option<Point> {
-        for (&point, &cell) in self.data.iter() {
-            if let Cell::Door(door_id) = cell {
-                if door_id == switch_id {
-                    return Some(point);
+
+    /// The cell on the point, None outside of the level.
+    pub fn cell(&self, point: Point) -> Option<Cell> {
+        self.data.get(&point).copied()
+    }
+
+    /// How the player dies when it tries to move from `from` to `to`, unless a powerup protects it.
+    pub fn collision(&self, from: Point, to: Point) -> Option<Death> {
+        if self.data.get(&from) != Some(&Cell::Player(Powerup::None)) {
+            return None;
+        }
+        match self.data.get(&to) {
+            Some(Cell::CounterClockwiseEnemy(_)) => Some(Death::Enemy),
+            Some(Cell::Void) => Some(Death::Void),
+            _ => None,
+        }
+    }
+    /// Points of the closed doors with the id, sorted.
+    pub fn door_positions(&self, switch_id: u8) -> Vec<Point> {
+        let mut positions: Vec<Point> = self
+            .data
+            .iter()
+            .filter(|(_, &cell)| cell == Cell::Door(switch_id))
+            .map(|(&point, _)| point)
+            .collect();
+        positions.sort();
+        positions
+    }
+
+    // Open or close all doors of the given id.
+    // A door that has something standing in it changes below it, and is closed once left.
+    fn set_doors(&mut self, switch_id: u8, open: bool) {
+        for cell in self.data.values_mut().chain(self.floor.values_mut()) {
+            match *cell {
+                Cell::Door(door_id) | Cell::OpenDoor(door_id) if door_id == switch_id => {
+                    *cell = if open {
+                        Cell::OpenDoor(door_id)
+                    } else {
+                        Cell::Door(door_id)
+                    }
                 }
+                _ => {}
             }
         }
-        None
     }
+
+    fn toggle_doors(&mut self, switch_id: u8) {
+        for cell in self.data.values_mut().chain(self.floor.values_mut()) {
+            match *cell {
+                Cell::Door(door_id) if door_id == switch_id => *cell = Cell::OpenDoor(door_id),
+                Cell::OpenDoor(door_id) if door_id == switch_id => *cell = Cell::Door(door_id),
+                _ => {}
+            }
+        }
+    }
+
+    // Doors of a pressure plate are open as long as any plate with the same id is held down.
+    fn update_pressure_plates(&mut self, switch_id: u8) {
+        let plate = Cell::PressurePlate(switch_id);
+        if !self.data.values().any(|&cell| cell == plate)
+            && !self.floor.values().any(|&cell| cell == plate)
+        {
+            return;
+        }
+        let pressed = self.floor.values().any(|&cell| cell == plate);
+        self.set_doors(switch_id, pressed);
+    }
+
+    // Place a player, enemy or box on the point, keeping track of the terrain below it.
+    fn occupy(&mut self, point: Point, occupant: Cell) {
+        match self.data.get(&point).cloned() {
+            Some(Cell::ToggleSwitch(switch_id)) => {
+                self.floor.insert(point, Cell::ToggleSwitch(switch_id));
+                self.toggle_doors(switch_id);
+            }
+            Some(Cell::PressurePlate(switch_id)) => {
+                self.floor.insert(point, Cell::PressurePlate(switch_id));
+                self.update(point, occupant);
+                self.update_pressure_plates(switch_id);
+            }
+            Some(Cell::OpenDoor(door_id)) => {
+                self.floor.insert(point, Cell::OpenDoor(door_id));
+            }
+            Some(Cell::Ice) => {
+                self.floor.insert(point, Cell::Ice);
+            }
+            Some(Cell::Conveyor(direction)) => {
+                self.floor.insert(point, Cell::Conveyor(direction));
+            }
+            Some(Cell::Sign) => {
+                self.floor.insert(point, Cell::Sign);
+            }
+            _ => {}
+        }
+        self.update(point, occupant);
+    }
+
+    // Remove a player, enemy or box from the point, restoring the terrain below it.
+    pub(crate) fn vacate(&mut self, point: Point) {
+        // a door closed while something stood in it comes back closed
+        let terrain = self.floor.remove(&point).unwrap_or(Cell::Empty);
+        self.update(point, terrain);
+        if let Cell::PressurePlate(switch_id) = terrain {
+            self.update_pressure_plates(switch_id);
+        }
+    }
+    // Push a box one cell further in the direction the player walks.
+    // Returns false when there is no room behind the box.
+    fn push_box(&mut self, player: Point, box_position: Point) -> bool {
+        let target = Point {
+            x: box_position.x + (box_position.x - player.x),
+            y: box_position.y + (box_position.y - player.y),
+        };
+        self.move_box(box_position, target)
+    }
+
+    fn move_box(&mut self, box_position: Point, target: Point) -> bool {
+        match self.data.get(&target).cloned() {
+            // the box falls into the void and fills it
+            Some(Cell::Void) => self.update(target, Cell::Empty),
+            Some(Cell::Empty)
+            | Some(Cell::PressurePlate(_))
+            | Some(Cell::ToggleSwitch(_))
+            | Some(Cell::OpenDoor(_))
+            | Some(Cell::Ice)
+            | Some(Cell::Conveyor(_)) => self.occupy(target, Cell::Box),
+            _ => return false,
+        }
+        self.vacate(box_position);
+        true
+    }
+
+    // A player without powerup dies when it is forced into the void or an enemy.
+    // Returns true if the player was removed from the map.
+    fn kill_player(&mut self, player: Point, target: Point) -> bool {
+        if let Some(Cell::Player(Powerup::None)) = self.data.get(&player) {
+            self.death = match self.data.get(&target) {
+                Some(Cell::Void) => Some(Death::Void),
+                Some(Cell::CounterClockwiseEnemy(_)) => Some(Death::Enemy),
+                _ => return false,
+            };
+            self.vacate(player);
+            return true;
+        }
+        false
+    }
+
+    /// How the player was removed from the map.
+    pub fn death(&self) -> Option<Death> {
+        self.death
+    }
+
+    /// Keys and powerups the player collected so far.
+    pub fn pickups(&self) -> u32 {
+        self.pickups
+    }
+
+    /// Name, intro, par values and everything else not on the map.
+    pub fn info(&self) -> &LevelInfo {
+        &self.info
+    }
+
+    pub fn set_info(&mut self, info: LevelInfo) {
+        self.info = info;
+    }
+
+    /// How far the player can see, None shows the whole level.
+    pub fn visibility(&self) -> Option<u8> {
+        self.visibility
+    }
+
+    pub fn set_visibility(&mut self, radius: Option<u8>) {
+        self.visibility = radius;
+    }
+
+    // Walls and closed doors block the line of sight
+    fn blocks_sight(&self, point: Point) -> bool {
+        matches!(
+            self.data.get(&point),
+            Some(Cell::HorizontalWall)
+                | Some(Cell::VerticalWall)
+                | Some(Cell::Door(_))
+                | Some(Cell::LockedDoor(_))
+        )
+    }
+
+    // Bresenham line between both points. The blocking cell itself is still visible.
+    fn in_line_of_sight(&self, from: Point, to: Point) -> bool {
+        let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+        let (step_x, step_y) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+        let mut error = dx + dy;
+        let mut current = from;
+        while current != to {
+            if current != from && self.blocks_sight(current) {
+                return false;
+            }
+            let double = 2 * error;
+            if double >= dy {
+                error += dy;
+                current.x += step_x;
+            }
+            if double <= dx {
+                error += dx;
+                current.y += step_y;
+            }
+        }
+        true
+    }
+
+    /// Cells the player can see right now. None when the whole level is visible.
+    pub fn visible_cells(&self) -> Option<HashSet<Point>> {
+        let radius = self.visibility()? as i32;
+        let player = match self.player_position() {
+            Some(player) => player,
+            None => return Some(HashSet::new()),
+        };
+        Some(
+            self.data
+                .keys()
+                .filter(|point| {
+                    let dx = (point.x - player.x) as i32;
+                    let dy = (point.y - player.y) as i32;
+                    dx * dx + dy * dy <= radius * radius && self.in_line_of_sight(player, **point)
+                })
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Remember everything the player sees right now, the terrain without anyone on it.
+    pub fn reveal(&mut self) {
+        if let Some(visible) = self.visible_cells() {
+            for point in visible {
+                let cell = match self.data[&point] {
+                    Cell::CounterClockwiseEnemy(_) | Cell::Player(_) => {
+                        self.floor.get(&point).cloned().unwrap_or(Cell::Empty)
+                    }
+                    cell => cell,
+                };
+                self.explored.insert(point, cell);
+            }
+        }
+    }
+
+    /// Whether the player has seen the point.
+    pub fn is_explored(&self, point: Point) -> bool {
+        self.explored.contains_key(&point)
+    }
+
+    /// What the player remembers of a cell out of sight, changes since it was last seen
+    /// are not known. None when it was never seen.
+    pub fn remembered(&self, point: Point) -> Option<Cell> {
+        self.explored.get(&point).copied()
+    }
+
+    /// Move everything standing on a conveyor one cell in its direction.
+    /// Returns the last position the player tried to enter, when it was moved.
+    pub fn update_conveyors(
+        &mut self,
+        max_x: i8,
+        max_y: i8,
+        inventory: &mut Inventory,
+    ) -> Option<Point> {
+        let mut conveyors: Vec<(Point, Direction)> = self
+            .floor
+            .iter()
+            .filter_map(|(&point, &cell)| match cell {
+                Cell::Conveyor(direction) => Some((point, direction)),
+                _ => None,
+            })
+            .collect();
+        conveyors.sort_by_key(|&(point, _)| point);
+
+        // everything is moved only once per update, even onto the next conveyor
+        let mut moved: Vec<Point> = vec![];
+        let mut player_target = None;
+        for (point, direction) in conveyors {
+            if moved.contains(&point) {
+                continue;
+            }
+            let target = neighbour(point, direction);
+            match self.data.get(&point).cloned() {
+                Some(Cell::Player(_)) => {
+                    if !self.kill_player(point, target) {
+                        player_target =
+                            Some(self.move_player(point, target, max_x, max_y, inventory));
+                    }
+                    if let Some(player) = self.player_position() {
+                        moved.push(player);
+                    }
+                }
+                Some(Cell::Box) if self.move_box(point, target) => moved.push(target),
+                // the enemy is gone when an invincible player ate it
+                Some(enemy @ Cell::CounterClockwiseEnemy(_))
+                    if self.enemy_enters(point, target, enemy)
+                        && self.cell(target) == Some(enemy) =>
+                {
+                    moved.push(target)
+                }
+                _ => {}
+            }
+        }
+        self.read_signs();
+        self.run_triggers();
+        player_target
+    }
+
+    /// Where the exit is.
     pub fn finish_position(&self) -> Option<Point> {
         for (&point, &cell) in self.data.iter() {
             if cell == Cell::Exit {
@@ -119,6 +630,7 @@ impl Level {
         None
     }
 
+    /// Where the player is, None once it was removed from the map.
     pub fn player_position(&self) -> Option<Point> {
         for (&point, &cell) in self.data.iter() {
             match cell {
@@ -130,37 +642,85 @@ impl Level {
         None
     }
 
-    fn move_player_regular(&mut self, player: Point, new_position: Point, player_struct: Cell) {
+    fn move_player_regular(
+        &mut self,
+        player: Point,
+        new_position: Point,
+        player_struct: Cell,
+        inventory: &mut Inventory,
+    ) {
         // Handle collosions here that will not reset the level
         if let Some(cell) = self.data.get(&new_position).cloned() {
             match cell {
                 // moved on empty space
                 Cell::Empty => {
-                    self.update(player, Cell::Empty);
-                    self.update(new_position, player_struct);
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
                 }
 
-                // Triggering a switch removes the switch and the related door
+                // Triggering a switch removes the switch and all related doors
                 Cell::Switch(switch_id) => {
-                    self.update(player, Cell::Empty);
+                    self.vacate(player);
                     self.update(new_position, player_struct);
-                    if let Some(door_position) = self.door_position(switch_id) {
+                    for door_position in self.door_positions(switch_id) {
                         self.update(door_position, Cell::Empty);
                     }
+                    // also the closed doors below an enemy or box
+                    for cell in self.floor.values_mut() {
+                        if *cell == Cell::Door(switch_id) {
+                            *cell = Cell::Empty;
+                        }
+                    }
+                }
+                // Toggle switches and pressure plates stay on the map,
+                // the doors are handled while occupying the cell
+                Cell::ToggleSwitch(_) | Cell::PressurePlate(_) | Cell::OpenDoor(_) => {
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
+                }
+                // Signs can be walked over, their text is shown by read_signs
+                Cell::Sign => {
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
+                }
+                // Stepping on ice, sliding is handled in move_player
+                // Conveyors move the player in update_conveyors
+                Cell::Ice | Cell::Conveyor(_) => {
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
                 }
                 // Triggering a teleporter, moves me to the destination
                 Cell::OneWayTeleporter(destination_point) => {
-                    self.update(player, Cell::Empty);
                     self.update(new_position, Cell::Empty);
-                    self.update(destination_point, player_struct);
+                    self.occupy(destination_point, player_struct);
+                    self.vacate(player);
                 }
                 //Triggering Invincibility Candy
                 Cell::Invincibility => {
-                    self.update(player, Cell::Empty);
+                    self.pickups += 1;
+                    self.vacate(player);
                     self.update(new_position, Cell::Player(Powerup::Invincible(5)));
                 }
+                // Walking into a box pushes it, if there is room behind it
+                Cell::Box if self.push_box(player, new_position) => {
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
+                }
+                // Keys are carried in the inventory
+                Cell::Key(color) => {
+                    inventory.add(color);
+                    self.pickups += 1;
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
+                }
+                // A locked door uses up a key of the same color and stays open
+                Cell::LockedDoor(color) if inventory.take(color) => {
+                    self.occupy(new_position, player_struct);
+                    self.vacate(player);
+                }
                 // Move over breakable ground. Replace with Void.
                 Cell::BreakableGround => {
+                    self.vacate(player);
                     self.update(player, Cell::Void);
                     self.update(new_position, player_struct);
                 }
@@ -170,7 +730,13 @@ impl Level {
         }
     }
 
-    fn move_player_invincible(&mut self, player: Point, new_position: Point, player_struct: Cell) {
+    fn move_player_invincible(
+        &mut self,
+        player: Point,
+        new_position: Point,
+        player_struct: Cell,
+        inventory: &mut Inventory,
+    ) {
         // we are invincible for the amount of "moves".
         // This allows us to eat enemies.
         // And to run over void.
@@ -186,20 +752,60 @@ impl Level {
         match target_cell {
             // We can run over void in invincibility
             Cell::Void => {
-                self.update(player, Cell::Empty);
+                self.vacate(player);
                 self.update(new_position, new_player_struct);
             }
             // we can remove enemies
             Cell::CounterClockwiseEnemy(_) => {
-                self.update(player, Cell::Empty);
+                self.vacate(player);
                 self.update(new_position, new_player_struct)
             }
             // else, handle normal movement
-            _ => self.move_player_regular(player, new_position, new_player_struct),
+            _ => self.move_player_regular(player, new_position, new_player_struct, inventory),
+        }
+    }
+
+    /// Moves the player one step and keeps it sliding while it stands on ice.
+    /// Returns the last position the player tried to enter.
+    pub fn move_player(
+        &mut self,
+        player: Point,
+        new_position: Point,
+        max_x: i8,
+        max_y: i8,
+        inventory: &mut Inventory,
+    ) -> Point {
+        let mut from = player;
+        let mut to = new_position;
+        self.move_player_step(from, to, max_x, max_y, inventory);
+        while from != to
+            && self.player_position() == Some(to)
+            && self.floor.get(&to) == Some(&Cell::Ice)
+        {
+            let next = Point {
+                x: to.x + (to.x - from.x),
+                y: to.y + (to.y - from.y),
+            };
+            from = to;
+            to = next;
+            if self.kill_player(from, to) {
+                break;
+            }
+            self.move_player_step(from, to, max_x, max_y, inventory);
         }
+        self.read_signs();
+        self.run_triggers();
+        to
     }
 
-    pub fn move_player(&mut self, player: Point, new_position: Point, max_x: i8, max_y: i8) {
+    fn move_player_step(
+        &mut self,
+        player: Point,
+        new_position: Point,
+        max_x: i8,
+        max_y: i8,
+        inventory: &mut Inventory,
+    ) {
         // Handle out of bounds
         if new_position.x >= 0
             && new_position.x <= max_x
@@ -209,17 +815,21 @@ impl Level {
             let player_struct = self.data.get(&player).cloned().unwrap();
             match player_struct {
                 Cell::Player(Powerup::None) => {
-                    self.move_player_regular(player, new_position, player_struct)
+                    self.move_player_regular(player, new_position, player_struct, inventory)
                 }
                 Cell::Player(Powerup::Invincible(_)) => {
-                    self.move_player_invincible(player, new_position, player_struct)
+                    self.move_player_invincible(player, new_position, player_struct, inventory)
                 }
 
                 _ => {}
             }
+            if let Some(moved) = self.player_position().filter(|&moved| moved != player) {
+                self.emit(TriggerEvent::PlayerEntered(moved));
+            }
         }
     }
 
+    /// Largest x and y of any cell, one less than the width and height.
     pub fn size(&self) -> (i8, i8) {
         let mut max_x = 0;
         let mut max_y = 0;
@@ -238,6 +848,7 @@ impl Level {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::key_color::KeyColor;
     #[test]
     fn test_empty() {
         let level = Level::empty(2, 2);
@@ -251,6 +862,311 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn test_collision() {
+        // @E
+        // ~.
+        let mut level = Level::empty(2, 2);
+        let player = Point { x: 0, y: 0 };
+        level.update(player, Cell::Player(Powerup::None));
+        level.update(
+            Point { x: 1, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Up),
+        );
+        level.update(Point { x: 0, y: 1 }, Cell::Void);
+        assert_eq!(level.cell(Point { x: 0, y: 1 }), Some(Cell::Void));
+        assert_eq!(level.cell(Point { x: 2, y: 0 }), None);
+        assert_eq!(
+            level.collision(player, Point { x: 1, y: 0 }),
+            Some(Death::Enemy)
+        );
+        assert_eq!(
+            level.collision(player, Point { x: 0, y: 1 }),
+            Some(Death::Void)
+        );
+        assert_eq!(level.collision(player, Point { x: 1, y: 1 }), None);
+        // the candy makes the player safe
+        level.update(player, Cell::Player(Powerup::Invincible(3)));
+        assert_eq!(level.collision(player, Point { x: 1, y: 0 }), None);
+    }
+
+    #[test]
+    fn test_visibility() {
+        // @..|.
+        // .....
+        let mut level = Level::empty(5, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 3, y: 0 }, Cell::VerticalWall);
+        assert!(level.visible_cells().is_none());
+
+        level.set_visibility(Some(4));
+        let visible = level.visible_cells().unwrap();
+        assert!(visible.contains(&Point { x: 2, y: 0 }));
+        // the wall itself can be seen, but not what is behind it
+        assert!(visible.contains(&Point { x: 3, y: 0 }));
+        assert!(!visible.contains(&Point { x: 4, y: 0 }));
+        assert!(visible.contains(&Point { x: 2, y: 1 }));
+
+        level.set_visibility(Some(1));
+        let visible = level.visible_cells().unwrap();
+        assert!(visible.contains(&Point { x: 1, y: 0 }));
+        assert!(!visible.contains(&Point { x: 2, y: 0 }));
+        assert!(!visible.contains(&Point { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn test_visibility_closed_door() {
+        // @D.
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Door(0));
+        level.set_visibility(Some(5));
+        assert!(!level
+            .visible_cells()
+            .unwrap()
+            .contains(&Point { x: 2, y: 0 }));
+        level.update(Point { x: 1, y: 0 }, Cell::OpenDoor(0));
+        assert!(level
+            .visible_cells()
+            .unwrap()
+            .contains(&Point { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn test_reveal() {
+        // @.§
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(
+            Point { x: 2, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Left),
+        );
+        level.set_visibility(Some(1));
+        level.reveal();
+        assert!(level.is_explored(Point { x: 1, y: 0 }));
+        assert!(!level.is_explored(Point { x: 2, y: 0 }));
+
+        level.set_visibility(Some(2));
+        level.reveal();
+        assert!(level.is_explored(Point { x: 2, y: 0 }));
+        // enemies are not remembered
+        assert!(level.remembered(Point { x: 2, y: 0 }) == Some(Cell::Empty));
+        assert!(level.remembered(Point { x: 1, y: 0 }) == Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_remembered_cells_do_not_change_out_of_view() {
+        // @.D
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 2, y: 0 }, Cell::Door(1));
+        level.set_visibility(Some(2));
+        level.reveal();
+        level.set_visibility(Some(1));
+        level.reveal();
+        // the door opens out of view
+        level.set_doors(1, true);
+        level.reveal();
+        assert_eq!(level.remembered(Point { x: 2, y: 0 }), Some(Cell::Door(1)));
+        assert_eq!(level.remembered(Point { x: 1, y: 0 }), Some(Cell::Empty));
+        // and is seen open once back in view
+        level.set_visibility(Some(2));
+        level.reveal();
+        assert_eq!(
+            level.remembered(Point { x: 2, y: 0 }),
+            Some(Cell::OpenDoor(1))
+        );
+    }
+
+    #[test]
+    fn test_trigger_on_player_entered() {
+        // @.D
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 2, y: 0 }, Cell::Door(1));
+        level.add_trigger(Trigger::new(
+            TriggerEvent::PlayerEntered(Point { x: 1, y: 0 }),
+            vec![
+                Action::OpenDoors(1),
+                Action::ShowMessage(String::from("Open sesame")),
+            ],
+        ));
+        let mut inventory = Inventory::new();
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert!(level.data.get(&Point { x: 2, y: 0 }) == Some(&Cell::OpenDoor(1)));
+        assert_eq!(level.message(), Some("Open sesame"));
+        // fired triggers are gone
+        assert!(level.triggers().is_empty());
+        // messages of triggers stay until the next one
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.message(), Some("Open sesame"));
+    }
+
+    #[test]
+    fn test_trigger_closes_door_behind_player() {
+        // @/.  -->  .@.  -->  .D@
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::OpenDoor(1));
+        level.add_trigger(Trigger::new(
+            TriggerEvent::PlayerEntered(Point { x: 1, y: 0 }),
+            vec![Action::CloseDoors(1)],
+        ));
+        let mut inventory = Inventory::new();
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.player_position(), Some(Point { x: 1, y: 0 }));
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.cell(Point { x: 1, y: 0 }), Some(Cell::Door(1)));
+    }
+
+    #[test]
+    fn test_repeating_trigger() {
+        // @.
+        let mut level = Level::empty(2, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.add_trigger(Trigger::repeating(
+            TriggerEvent::PlayerEntered(Point { x: 0, y: 0 }),
+            vec![Action::SpawnEnemy(Point { x: 1, y: 0 }, Direction::Up)],
+        ));
+        let mut inventory = Inventory::new();
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            1,
+            0,
+            &mut inventory,
+        );
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 0 },
+            1,
+            0,
+            &mut inventory,
+        );
+        assert!(
+            level.data.get(&Point { x: 1, y: 0 })
+                == Some(&Cell::CounterClockwiseEnemy(Direction::Up))
+        );
+        assert_eq!(level.triggers().len(), 1);
+    }
+
+    #[test]
+    fn test_spawn_enemy_needs_room() {
+        // @D
+        let mut level = Level::empty(2, 1);
+        level.update(Point { x: 1, y: 0 }, Cell::Door(0));
+        level.add_trigger(Trigger::new(
+            TriggerEvent::TimerExpired(0),
+            vec![Action::SpawnEnemy(Point { x: 1, y: 0 }, Direction::Up)],
+        ));
+        level.run_action(Action::StartTimer { id: 0, ticks: 1 });
+        level.update_enemies();
+        assert!(level.data.get(&Point { x: 1, y: 0 }) == Some(&Cell::Door(0)));
+    }
+
+    #[test]
+    fn test_timer_and_enemy_trigger() {
+        // >.
+        // O.
+        let mut level = Level::empty(2, 2);
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Right),
+        );
+        level.update(Point { x: 0, y: 1 }, Cell::OpenDoor(2));
+        level.add_trigger(Trigger::new(
+            TriggerEvent::EnemyEntered(Point { x: 1, y: 0 }),
+            vec![Action::StartTimer { id: 7, ticks: 2 }],
+        ));
+        level.add_trigger(Trigger::new(
+            TriggerEvent::TimerExpired(7),
+            vec![Action::CloseDoors(2)],
+        ));
+        level.update_enemies();
+        // the enemy rotates at the border from now on
+        level.update_enemies();
+        assert!(level.data.get(&Point { x: 0, y: 1 }) == Some(&Cell::OpenDoor(2)));
+        level.update_enemies();
+        assert!(level.data.get(&Point { x: 0, y: 1 }) == Some(&Cell::Door(2)));
+    }
+
+    #[test]
+    fn test_read_sign() {
+        // @..!
+        let mut level = Level::empty(4, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.add_sign(Point { x: 3, y: 0 }, "Hello");
+        assert_eq!(level.sign(Point { x: 3, y: 0 }), Some("Hello"));
+        let mut inventory = Inventory::new();
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.message(), None);
+        // next to the sign
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.message(), Some("Hello"));
+        // the sign stays when walking over it
+        level.move_player(
+            Point { x: 2, y: 0 },
+            Point { x: 3, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        assert!(level.data.get(&Point { x: 3, y: 0 }) == Some(&Cell::Player(Powerup::None)));
+        level.move_player(
+            Point { x: 3, y: 0 },
+            Point { x: 2, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        assert!(level.data.get(&Point { x: 3, y: 0 }) == Some(&Cell::Sign));
+        // walking away from the sign hides its text
+        level.move_player(
+            Point { x: 2, y: 0 },
+            Point { x: 1, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.message(), None);
+    }
+
     #[test]
     fn test_size() {
         let level = Level::empty(3, 2);
@@ -315,7 +1231,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: -1, y: 0 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: -1, y: 0 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
         } else {
             panic!("Test failed");
@@ -326,7 +1248,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 2, y: 0 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 2, y: 0 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
         } else {
             panic!("Test failed");
@@ -337,8 +1265,14 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 2 }, 1, 1);
-        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 2 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
         } else {
             panic!("Test failed");
         }
@@ -349,7 +1283,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 1 }) {
         } else {
             panic!("Test failed");
@@ -360,7 +1300,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 1, y: 1 }, Cell::Player(Powerup::None));
         level.update(Point { x: 0, y: 0 }, Cell::Exit);
-        level.move_player(Point { x: 1, y: 1 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 1, y: 1 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 1 }) {
         } else {
             panic!("Test failed");
@@ -372,7 +1318,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 1 }) {
         } else {
             panic!("Test failed");
@@ -383,7 +1335,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 1, y: 1 }, Cell::Player(Powerup::None));
         level.update(Point { x: 0, y: 0 }, Cell::Exit);
-        level.move_player(Point { x: 1, y: 1 }, Point { x: 1, y: 0 }, 1, 1);
+        level.move_player(
+            Point { x: 1, y: 1 },
+            Point { x: 1, y: 0 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 1, y: 0 }) {
         } else {
             panic!("Test failed");
@@ -397,7 +1355,13 @@ mod tests {
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 0, y: 1 }, Cell::BreakableGround);
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Void) = level.data.get(&Point { x: 0, y: 0 }) {
         } else {
             panic!("Test failed");
@@ -412,11 +1376,18 @@ mod tests {
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
         level.update(Point { x: 0, y: 1 }, Cell::Invincibility);
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::Invincible(5))) = level.data.get(&Point { x: 0, y: 1 }) {
         } else {
             panic!("Test failed");
         }
+        assert_eq!(level.pickups(), 1);
     }
 
     #[test]
@@ -426,7 +1397,13 @@ mod tests {
         let mut level = Level::empty(2, 2);
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::Invincible(0)));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
         if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 1 }) {
         } else {
             panic!("Test failed");
@@ -445,7 +1422,13 @@ mod tests {
             Cell::OneWayTeleporter(Point { x: 2, y: 2 }),
         );
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
 
         // Test teleporter removed
         if let Some(Cell::Empty) = level.data.get(&Point { x: 0, y: 1 }) {
@@ -471,7 +1454,13 @@ mod tests {
             Cell::OneWayTeleporter(Point { x: 2, y: 2 }),
         );
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
 
         // Test teleporter removed
         if let Some(Cell::Empty) = level.data.get(&Point { x: 0, y: 1 }) {
@@ -495,7 +1484,13 @@ mod tests {
         level.update(Point { x: 0, y: 1 }, Cell::Switch(1));
         level.update(Point { x: 1, y: 2 }, Cell::Door(1));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
 
         // Test Door removed
         if let Some(Cell::Empty) = level.data.get(&Point { x: 1, y: 2 }) {
@@ -510,6 +1505,723 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_move_on_switch_opens_all_doors() {
+        // @D.  -->  .D.
+        // S..       @..
+        // D.D       ...
+        let mut level = Level::empty(3, 3);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 0, y: 1 }, Cell::Switch(1));
+        level.update(Point { x: 1, y: 0 }, Cell::Door(2));
+        level.update(Point { x: 0, y: 2 }, Cell::Door(1));
+        level.update(Point { x: 2, y: 2 }, Cell::Door(1));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            2,
+            2,
+            &mut Inventory::new(),
+        );
+
+        assert!(level.door_positions(1).is_empty());
+        assert_eq!(level.door_positions(2), vec![Point { x: 1, y: 0 }]);
+    }
+
+    #[test]
+    fn test_move_on_toggle_switch() {
+        // @s.  -->  .@.  -->  @s.  -->  .@.
+        // D.D       /./       D.D       /./
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::ToggleSwitch(1));
+        level.update(Point { x: 0, y: 1 }, Cell::Door(1));
+        level.update(Point { x: 2, y: 1 }, Cell::Door(1));
+
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        assert!(level.door_positions(1).is_empty());
+        if let Some(Cell::OpenDoor(1)) = level.data.get(&Point { x: 2, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+
+        // stepping off keeps the doors open and leaves the switch behind
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::ToggleSwitch(1)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert!(level.door_positions(1).is_empty());
+
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        assert_eq!(
+            level.door_positions(1),
+            vec![Point { x: 0, y: 1 }, Point { x: 2, y: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_move_on_pressure_plate() {
+        // @_.  -->  .@.  -->  @_.
+        // D.D       /./       D.D
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::PressurePlate(1));
+        level.update(Point { x: 0, y: 1 }, Cell::Door(1));
+        level.update(Point { x: 2, y: 1 }, Cell::Door(1));
+
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        assert!(level.door_positions(1).is_empty());
+
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::PressurePlate(1)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert_eq!(
+            level.door_positions(1),
+            vec![Point { x: 0, y: 1 }, Point { x: 2, y: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_door_stays_open_while_occupied() {
+        // @/.  -->  _@.  -->  _D@
+        // the player starts on top of the pressure plate
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level
+            .floor
+            .insert(Point { x: 0, y: 0 }, Cell::PressurePlate(1));
+        level.update(Point { x: 1, y: 0 }, Cell::OpenDoor(1));
+
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut Inventory::new(),
+        );
+        // plate released, but the player is still standing in the door
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            2,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Door(1)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_door_closes_after_toggle_while_occupied() {
+        // /@Bs.  -->  D.@B.
+        // the player stands in the open door and pushes the box onto the toggle switch
+        let mut level = Level::empty(5, 1);
+        level.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::None));
+        level.floor.insert(Point { x: 1, y: 0 }, Cell::OpenDoor(1));
+        level.update(Point { x: 0, y: 0 }, Cell::OpenDoor(1));
+        level.update(Point { x: 2, y: 0 }, Cell::Box);
+        level.update(Point { x: 3, y: 0 }, Cell::ToggleSwitch(1));
+
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            4,
+            0,
+            &mut Inventory::new(),
+        );
+        assert_eq!(level.cell(Point { x: 0, y: 0 }), Some(Cell::Door(1)));
+        assert_eq!(level.cell(Point { x: 1, y: 0 }), Some(Cell::Door(1)));
+        assert_eq!(level.cell(Point { x: 3, y: 0 }), Some(Cell::Box));
+    }
+
+    #[test]
+    fn test_enemy_holds_pressure_plate() {
+        // §_D  -->  .§/
+        let mut level = Level::empty(3, 1);
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Right),
+        );
+        level.update(Point { x: 1, y: 0 }, Cell::PressurePlate(1));
+        level.update(Point { x: 2, y: 0 }, Cell::Door(1));
+        level.update_enemies();
+        if let Some(Cell::OpenDoor(1)) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_pick_up_key() {
+        // @k.  -->  .@.
+        let mut level = Level::empty(3, 1);
+        let mut inventory = Inventory::new();
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Key(KeyColor::Red));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert_eq!(inventory.count(KeyColor::Red), 1);
+        assert_eq!(level.pickups(), 1);
+        // the key is gone once the player walks on
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(level.cell(Point { x: 1, y: 0 }), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_locked_door_without_key() {
+        // @L.  -->  @L.
+        let mut level = Level::empty(3, 1);
+        let mut inventory = Inventory::new();
+        inventory.add(KeyColor::Blue);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::LockedDoor(KeyColor::Red));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert_eq!(inventory.count(KeyColor::Blue), 1);
+    }
+
+    #[test]
+    fn test_locked_door_uses_up_key() {
+        // @kLL  -->  ..@L
+        let mut level = Level::empty(4, 1);
+        let mut inventory = Inventory::new();
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Key(KeyColor::Red));
+        level.update(Point { x: 2, y: 0 }, Cell::LockedDoor(KeyColor::Red));
+        level.update(Point { x: 3, y: 0 }, Cell::LockedDoor(KeyColor::Red));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        level.move_player(
+            Point { x: 2, y: 0 },
+            Point { x: 3, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::LockedDoor(KeyColor::Red)) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert!(inventory.is_empty());
+    }
+
+    #[test]
+    fn test_push_box() {
+        // @#.  -->  .@#
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Box);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Box) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_push_box_blocked() {
+        // @##  -->  @##
+        // .#|       .#|
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Box);
+        level.update(Point { x: 2, y: 0 }, Cell::Box);
+        level.update(Point { x: 1, y: 1 }, Cell::Box);
+        level.update(Point { x: 2, y: 1 }, Cell::VerticalWall);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        // a box can not be pushed out of the map either
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        level.move_player(
+            Point { x: 0, y: 1 },
+            Point { x: 1, y: 1 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert!(level.data.get(&Point { x: 1, y: 0 }) == Some(&Cell::Box));
+        assert!(level.data.get(&Point { x: 2, y: 0 }) == Some(&Cell::Box));
+        assert!(level.data.get(&Point { x: 1, y: 1 }) == Some(&Cell::Box));
+    }
+
+    #[test]
+    fn test_push_box_into_void() {
+        // @# .  -->  .@..
+        let mut level = Level::empty(4, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Box);
+        level.update(Point { x: 2, y: 0 }, Cell::Void);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            3,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Empty) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Empty) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_push_box_on_pressure_plate() {
+        // @#_D  -->  .@#/  -->  ..@#
+        let mut level = Level::empty(5, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Box);
+        level.update(Point { x: 2, y: 0 }, Cell::PressurePlate(1));
+        level.update(Point { x: 3, y: 0 }, Cell::Door(1));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            4,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::OpenDoor(1)) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+
+        // the player now holds the plate down, so the box can be pushed through the door
+        level.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            4,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Box) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        assert!(level.floor.get(&Point { x: 2, y: 0 }) == Some(&Cell::PressurePlate(1)));
+    }
+
+    #[test]
+    fn test_update_enemies_rotate_on_box() {
+        // §#  -->  §#
+        let mut level = Level::empty(2, 1);
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Right),
+        );
+        level.update(Point { x: 1, y: 0 }, Cell::Box);
+        level.update_enemies();
+        if let Some(Cell::CounterClockwiseEnemy(Direction::Up)) =
+            level.data.get(&Point { x: 0, y: 0 })
+        {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_slide_on_ice_until_wall() {
+        // @~~~|  -->  ...@|
+        let mut level = Level::empty(5, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        for x in 1..4 {
+            level.update(Point { x, y: 0 }, Cell::Ice);
+        }
+        level.update(Point { x: 4, y: 0 }, Cell::VerticalWall);
+        let target = level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            4,
+            0,
+            &mut Inventory::new(),
+        );
+        assert_eq!(target, Point { x: 4, y: 0 });
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        // the ice stays behind the player
+        if let Some(Cell::Ice) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_slide_on_ice_until_ground() {
+        // @~~..  -->  ~~~@.
+        let mut level = Level::empty(5, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Ice);
+        level.update(Point { x: 2, y: 0 }, Cell::Ice);
+        let target = level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            4,
+            0,
+            &mut Inventory::new(),
+        );
+        assert_eq!(target, Point { x: 3, y: 0 });
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 3, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_slide_on_ice_until_map_border() {
+        // @~~  -->  .~@
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Ice);
+        level.update(Point { x: 2, y: 0 }, Cell::Ice);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_slide_on_ice_into_void() {
+        // @~~ .  -->  .~~ .
+        let mut level = Level::empty(5, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Ice);
+        level.update(Point { x: 2, y: 0 }, Cell::Ice);
+        level.update(Point { x: 3, y: 0 }, Cell::Void);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            4,
+            0,
+            &mut Inventory::new(),
+        );
+        assert_eq!(level.player_position(), None);
+        if let Some(Cell::Ice) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_slide_on_ice_into_teleporter() {
+        // @~T  -->  .~.
+        // ...       @..
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Ice);
+        level.update(
+            Point { x: 2, y: 0 },
+            Cell::OneWayTeleporter(Point { x: 0, y: 1 }),
+        );
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            1,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_standing_still_on_ice() {
+        // a key without movement must not make the player slide
+        let mut level = Level::empty(2, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.floor.insert(Point { x: 0, y: 0 }, Cell::Ice);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 0 },
+            1,
+            0,
+            &mut Inventory::new(),
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 0, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_conveyor_moves_player() {
+        // @>.  -->  .@.  -->  .>@
+        let mut level = Level::empty(3, 1);
+        let mut inventory = Inventory::new();
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+
+        let target = level.update_conveyors(2, 0, &mut inventory);
+        assert_eq!(target, Some(Point { x: 2, y: 0 }));
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Conveyor(Direction::Right)) = level.data.get(&Point { x: 1, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_conveyor_moves_once_per_update() {
+        // @>>.  -->  .@>.  -->  .>@.
+        let mut level = Level::empty(4, 1);
+        let mut inventory = Inventory::new();
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.update(Point { x: 2, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            3,
+            0,
+            &mut inventory,
+        );
+        level.update_conveyors(3, 0, &mut inventory);
+        if let Some(Cell::Player(Powerup::None)) = level.data.get(&Point { x: 2, y: 0 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_conveyor_moves_player_into_void() {
+        // @v  -->  .v
+        // .        .
+        let mut level = Level::empty(2, 2);
+        let mut inventory = Inventory::new();
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level
+            .floor
+            .insert(Point { x: 0, y: 0 }, Cell::Conveyor(Direction::Down));
+        level.update(Point { x: 0, y: 1 }, Cell::Void);
+        assert_eq!(level.update_conveyors(1, 1, &mut inventory), None);
+        assert_eq!(level.player_position(), None);
+        assert_eq!(level.death(), Some(Death::Void));
+    }
+
+    #[test]
+    fn test_death_by_enemy() {
+        // §@  -->  .§
+        let mut level = Level::empty(2, 1);
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Right),
+        );
+        level.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::None));
+        assert_eq!(level.death(), None);
+        level.update_enemies();
+        assert_eq!(level.player_position(), None);
+        assert_eq!(level.death(), Some(Death::Enemy));
+    }
+
+    #[test]
+    fn test_conveyor_moves_enemy_and_box() {
+        // §.  -->  ..
+        // #.       .§
+        //          .#
+        let mut level = Level::empty(2, 3);
+        let mut inventory = Inventory::new();
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Up),
+        );
+        level
+            .floor
+            .insert(Point { x: 0, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.update(Point { x: 0, y: 1 }, Cell::Box);
+        level
+            .floor
+            .insert(Point { x: 0, y: 1 }, Cell::Conveyor(Direction::Right));
+        level.update_conveyors(1, 2, &mut inventory);
+        if let Some(Cell::CounterClockwiseEnemy(Direction::Up)) =
+            level.data.get(&Point { x: 1, y: 0 })
+        {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Box) = level.data.get(&Point { x: 1, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+        if let Some(Cell::Conveyor(Direction::Right)) = level.data.get(&Point { x: 0, y: 1 }) {
+        } else {
+            panic!("Test failed");
+        }
+    }
+
+    #[test]
+    fn test_conveyor_moves_enemy_into_invincible_player() {
+        // §5  -->  >5
+        let mut level = Level::empty(2, 1);
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Up),
+        );
+        level
+            .floor
+            .insert(Point { x: 0, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::Invincible(5)));
+        level.update_conveyors(1, 0, &mut Inventory::new());
+        assert_eq!(
+            level.cell(Point { x: 0, y: 0 }),
+            Some(Cell::Conveyor(Direction::Right))
+        );
+        assert_eq!(
+            level.cell(Point { x: 1, y: 0 }),
+            Some(Cell::Player(Powerup::Invincible(5)))
+        );
+        assert_eq!(level.death(), None);
+    }
+
+    #[test]
+    fn test_conveyor_moves_enemy_into_player() {
+        // §@  -->  >§
+        let mut level = Level::empty(2, 1);
+        level.update(
+            Point { x: 0, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Up),
+        );
+        level
+            .floor
+            .insert(Point { x: 0, y: 0 }, Cell::Conveyor(Direction::Right));
+        level.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::None));
+        level.update_conveyors(1, 0, &mut Inventory::new());
+        assert_eq!(level.player_position(), None);
+        assert_eq!(level.death(), Some(Death::Enemy));
+    }
+
     #[test]
     fn test_move_as_invinciblle_on_switch() {
         // 5..  -->  ...
@@ -520,7 +2232,13 @@ mod tests {
         level.update(Point { x: 0, y: 1 }, Cell::Switch(1));
         level.update(Point { x: 1, y: 2 }, Cell::Door(1));
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
 
         // Test Door removed
         if let Some(Cell::Empty) = level.data.get(&Point { x: 1, y: 2 }) {
@@ -544,7 +2262,13 @@ mod tests {
         level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::Invincible(5)));
         level.update(Point { x: 0, y: 1 }, Cell::Void);
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
 
         // Test Player position
         if let Some(Cell::Player(Powerup::Invincible(4))) = level.data.get(&Point { x: 0, y: 1 }) {
@@ -570,7 +2294,13 @@ mod tests {
             Cell::CounterClockwiseEnemy(Direction::Up),
         );
         level.update(Point { x: 1, y: 1 }, Cell::Exit);
-        level.move_player(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 1, 1);
+        level.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            1,
+            1,
+            &mut Inventory::new(),
+        );
 
         // Test Player position
         if let Some(Cell::Player(Powerup::Invincible(4))) = level.data.get(&Point { x: 0, y: 1 }) {
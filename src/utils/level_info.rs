@@ -0,0 +1,57 @@
+/// Milliseconds between two enemy moves, unless a level says otherwise.
+pub const DEFAULT_ENEMY_INTERVAL: u64 = 500;
+
+/// Everything about a level that is not part of its map.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LevelInfo {
+    pub name: String,
+    pub author: String,
+    /// shown before the level starts, empty to start right away
+    pub intro: String,
+    /// shown together with the help overlay
+    pub hints: Vec<String>,
+    /// time in ms a good run needs, finishing within it gives bonus points
+    pub par_time: Option<u128>,
+    /// moves a good run needs, finishing within them gives bonus points
+    pub par_moves: Option<u32>,
+    /// milliseconds between two enemy moves
+    pub enemy_interval: u64,
+}
+
+impl LevelInfo {
+    pub fn new(name: &str) -> Self {
+        LevelInfo {
+            name: String::from(name),
+            author: String::new(),
+            intro: String::new(),
+            hints: vec![],
+            par_time: None,
+            par_moves: None,
+            enemy_interval: DEFAULT_ENEMY_INTERVAL,
+        }
+    }
+}
+
+impl Default for LevelInfo {
+    fn default() -> Self {
+        LevelInfo::new("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LevelInfo;
+    use super::DEFAULT_ENEMY_INTERVAL;
+
+    #[test]
+    fn new() {
+        let info = LevelInfo::new("First steps");
+        assert_eq!(info.name, "First steps");
+        assert!(info.intro.is_empty());
+        assert!(info.hints.is_empty());
+        assert_eq!(info.par_time, None);
+        assert_eq!(info.par_moves, None);
+        assert_eq!(info.enemy_interval, DEFAULT_ENEMY_INTERVAL);
+        assert!(LevelInfo::default().name.is_empty());
+    }
+}
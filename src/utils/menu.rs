@@ -0,0 +1,297 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+
+#[derive(Clone, PartialEq, Copy, Eq, Debug)]
+pub enum MenuEntry {
+    Play,
+    Continue,
+    LevelSelect,
+    Settings,
+    Quit,
+}
+
+pub const MENU_ENTRIES: [MenuEntry; 5] = [
+    MenuEntry::Play,
+    MenuEntry::Continue,
+    MenuEntry::LevelSelect,
+    MenuEntry::Settings,
+    MenuEntry::Quit,
+];
+
+/// lives that can be chosen in the settings, None for infinite retries
+pub const LIVES_SETTINGS: [Option<u8>; 4] = [None, Some(3), Some(5), Some(9)];
+
+impl MenuEntry {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuEntry::Play => "Play",
+            MenuEntry::Continue => "Continue",
+            MenuEntry::LevelSelect => "Level select",
+            MenuEntry::Settings => "Settings",
+            MenuEntry::Quit => "Quit",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Copy, Eq, Debug)]
+pub enum Screen {
+    Main,
+    LevelSelect,
+    Settings,
+}
+
+#[derive(Clone, PartialEq, Copy, Eq, Debug)]
+pub enum MenuAction {
+    None,
+    /// play all levels, starting with the given level index
+    Play(usize),
+    /// play only the level with the given index
+    PlaySingle(usize),
+    Quit,
+}
+
+/// Title screen state. Uses the same keys as the game:
+/// arrow keys to navigate, right to select, left to go back, Esc or q to quit.
+pub struct Menu {
+    screen: Screen,
+    selected: usize,
+    level_count: usize,
+    lives: Option<u8>,
+    action: MenuAction,
+}
+
+impl Menu {
+    pub fn new(level_count: usize, lives: Option<u8>) -> Self {
+        Menu {
+            screen: Screen::Main,
+            selected: 0,
+            level_count,
+            lives,
+            action: MenuAction::None,
+        }
+    }
+
+    pub fn screen(&self) -> Screen {
+        self.screen
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.level_count
+    }
+
+    pub fn lives(&self) -> Option<u8> {
+        self.lives
+    }
+
+    pub fn action(&self) -> MenuAction {
+        self.action
+    }
+
+    fn with(&self, screen: Screen, selected: usize, lives: Option<u8>, action: MenuAction) -> Self {
+        Menu {
+            screen,
+            selected,
+            level_count: self.level_count,
+            lives,
+            action,
+        }
+    }
+
+    fn entry_count(&self) -> usize {
+        match self.screen {
+            Screen::Main => MENU_ENTRIES.len(),
+            Screen::LevelSelect => self.level_count,
+            Screen::Settings => 1,
+        }
+    }
+
+    fn next_lives(&self) -> Option<u8> {
+        let current = LIVES_SETTINGS
+            .iter()
+            .position(|&lives| lives == self.lives)
+            .unwrap_or(0);
+        LIVES_SETTINGS[(current + 1) % LIVES_SETTINGS.len()]
+    }
+
+    fn select(&self, unlocked: usize) -> Self {
+        match self.screen {
+            Screen::Main => match MENU_ENTRIES[self.selected] {
+                MenuEntry::Play => {
+                    self.with(Screen::Main, self.selected, self.lives, MenuAction::Play(0))
+                }
+                MenuEntry::Continue => {
+                    let level_index = unlocked.min(self.level_count).saturating_sub(1);
+                    self.with(
+                        Screen::Main,
+                        self.selected,
+                        self.lives,
+                        MenuAction::Play(level_index),
+                    )
+                }
+                MenuEntry::LevelSelect => {
+                    self.with(Screen::LevelSelect, 0, self.lives, MenuAction::None)
+                }
+                MenuEntry::Settings => self.with(Screen::Settings, 0, self.lives, MenuAction::None),
+                MenuEntry::Quit => {
+                    self.with(Screen::Main, self.selected, self.lives, MenuAction::Quit)
+                }
+            },
+            Screen::LevelSelect if self.selected < unlocked => self.with(
+                Screen::LevelSelect,
+                self.selected,
+                self.lives,
+                MenuAction::PlaySingle(self.selected),
+            ),
+            Screen::LevelSelect => self.with(
+                Screen::LevelSelect,
+                self.selected,
+                self.lives,
+                MenuAction::None,
+            ),
+            Screen::Settings => self.with(Screen::Settings, 0, self.next_lives(), MenuAction::None),
+        }
+    }
+
+    fn back(&self) -> Self {
+        let entry = match self.screen {
+            Screen::Main => {
+                return self.with(Screen::Main, self.selected, self.lives, MenuAction::Quit)
+            }
+            Screen::LevelSelect => MenuEntry::LevelSelect,
+            Screen::Settings => MenuEntry::Settings,
+        };
+        let selected = MENU_ENTRIES.iter().position(|&e| e == entry).unwrap_or(0);
+        self.with(Screen::Main, selected, self.lives, MenuAction::None)
+    }
+
+    /// Handle a key press. `unlocked` is the number of levels that can be played.
+    pub fn update(&self, event: KeyEvent, unlocked: usize) -> Self {
+        let count = self.entry_count();
+        match event.code {
+            KeyCode::Up => self.with(
+                self.screen,
+                (self.selected + count - 1) % count,
+                self.lives,
+                MenuAction::None,
+            ),
+            KeyCode::Down => self.with(
+                self.screen,
+                (self.selected + 1) % count,
+                self.lives,
+                MenuAction::None,
+            ),
+            KeyCode::Right | KeyCode::Enter => self.select(unlocked),
+            KeyCode::Left if self.screen != Screen::Main => self.back(),
+            KeyCode::Esc => self.back(),
+            KeyCode::Char('q') => {
+                self.with(self.screen, self.selected, self.lives, MenuAction::Quit)
+            }
+            _ => self.with(self.screen, self.selected, self.lives, MenuAction::None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Menu;
+    use super::MenuAction;
+    use super::Screen;
+    use crossterm::event::KeyCode;
+    use crossterm::event::KeyEvent;
+    use crossterm::event::KeyModifiers;
+
+    fn press(menu: &Menu, code: KeyCode, unlocked: usize) -> Menu {
+        menu.update(KeyEvent::new(code, KeyModifiers::NONE), unlocked)
+    }
+
+    #[test]
+    fn new() {
+        let menu = Menu::new(5, None);
+        assert_eq!(menu.screen(), Screen::Main);
+        assert_eq!(menu.selected(), 0);
+        assert_eq!(menu.level_count(), 5);
+        assert_eq!(menu.action(), MenuAction::None);
+    }
+
+    #[test]
+    fn navigate_and_play() {
+        let menu = Menu::new(5, None);
+        let menu = press(&menu, KeyCode::Up, 1);
+        assert_eq!(menu.selected(), 4);
+        let menu = press(&menu, KeyCode::Down, 1);
+        assert_eq!(menu.selected(), 0);
+        let menu = press(&menu, KeyCode::Right, 1);
+        assert_eq!(menu.action(), MenuAction::Play(0));
+        // the action is only reported once
+        let menu = press(&menu, KeyCode::Char('a'), 1);
+        assert_eq!(menu.action(), MenuAction::None);
+    }
+
+    #[test]
+    fn continue_with_last_unlocked_level() {
+        let menu = press(&Menu::new(5, None), KeyCode::Down, 3);
+        let menu = press(&menu, KeyCode::Enter, 3);
+        assert_eq!(menu.action(), MenuAction::Play(2));
+        // all levels finished, continue with the last one
+        let menu = press(&menu, KeyCode::Enter, 6);
+        assert_eq!(menu.action(), MenuAction::Play(4));
+    }
+
+    #[test]
+    fn level_select() {
+        let mut menu = Menu::new(5, None);
+        for _ in 0..2 {
+            menu = press(&menu, KeyCode::Down, 2);
+        }
+        let menu = press(&menu, KeyCode::Right, 2);
+        assert_eq!(menu.screen(), Screen::LevelSelect);
+        let menu = press(&menu, KeyCode::Down, 2);
+        let menu = press(&menu, KeyCode::Right, 2);
+        assert_eq!(menu.action(), MenuAction::PlaySingle(1));
+
+        // locked levels can not be played
+        let menu = press(&menu, KeyCode::Down, 2);
+        let menu = press(&menu, KeyCode::Right, 2);
+        assert_eq!(menu.action(), MenuAction::None);
+
+        let menu = press(&menu, KeyCode::Left, 2);
+        assert_eq!(menu.screen(), Screen::Main);
+        assert_eq!(menu.selected(), 2);
+    }
+
+    #[test]
+    fn settings() {
+        let mut menu = Menu::new(5, None);
+        for _ in 0..3 {
+            menu = press(&menu, KeyCode::Down, 1);
+        }
+        let menu = press(&menu, KeyCode::Right, 1);
+        assert_eq!(menu.screen(), Screen::Settings);
+        let menu = press(&menu, KeyCode::Right, 1);
+        assert_eq!(menu.lives(), Some(3));
+        let menu = press(&menu, KeyCode::Enter, 1);
+        let menu = press(&menu, KeyCode::Right, 1);
+        assert_eq!(menu.lives(), Some(9));
+        // left goes back like on every other screen
+        let menu = press(&menu, KeyCode::Left, 1);
+        assert_eq!(menu.screen(), Screen::Main);
+        assert_eq!(menu.selected(), 3);
+        assert_eq!(menu.lives(), Some(9));
+    }
+
+    #[test]
+    fn quit() {
+        let menu = Menu::new(5, None);
+        assert_eq!(press(&menu, KeyCode::Esc, 1).action(), MenuAction::Quit);
+        assert_eq!(
+            press(&menu, KeyCode::Char('q'), 1).action(),
+            MenuAction::Quit
+        );
+        let menu = press(&menu, KeyCode::Up, 1);
+        assert_eq!(press(&menu, KeyCode::Right, 1).action(), MenuAction::Quit);
+    }
+}
@@ -1,7 +1,25 @@
 pub mod cell;
+pub mod clock;
+pub mod config;
+pub mod daily;
+pub mod death;
 pub mod direction;
 pub mod drawing;
 pub mod game_state;
+pub mod generator;
+pub mod input;
+pub mod inventory;
+pub mod key_color;
 pub mod level;
+pub mod level_info;
+pub mod menu;
+pub mod narration;
+pub mod palette;
 pub mod point;
 pub mod powerup;
+pub mod progress;
+pub mod recording;
+pub mod snapshot;
+pub mod solver;
+pub mod stats;
+pub mod trigger;
@@ -0,0 +1,451 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::drawing::format_time;
+use crate::utils::drawing::menu_entries;
+use crate::utils::drawing::MENU_KEYS;
+use crate::utils::inventory::Inventory;
+use crate::utils::key_color::KeyColor;
+use crate::utils::level::neighbour;
+use crate::utils::level::Level;
+use crate::utils::level_info::LevelInfo;
+use crate::utils::menu::Menu;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+use crate::utils::progress::Progress;
+use crate::utils::stats::total_score;
+use crate::utils::stats::totals;
+use crate::utils::stats::LevelStats;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+// Text descriptions of a level for the accessible mode, read out by a screen reader.
+
+const DIRECTIONS: [(Direction, &str); 4] = [
+    (Direction::Up, "north"),
+    (Direction::Right, "east"),
+    (Direction::Down, "south"),
+    (Direction::Left, "west"),
+];
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+// What a cell is called, and what is said after the distance, like the direction of an enemy.
+// None for cells that are walked over without noticing them.
+fn cell_name(cell: Cell) -> Option<(String, String)> {
+    let suffix = match cell {
+        Cell::Empty | Cell::Player(_) => return None,
+        Cell::CounterClockwiseEnemy(direction) | Cell::Conveyor(direction) => {
+            format!(" moving {}", direction_name(direction))
+        }
+        _ => String::new(),
+    };
+    let name = match cell {
+        Cell::Switch(id)
+        | Cell::Door(id)
+        | Cell::OpenDoor(id)
+        | Cell::ToggleSwitch(id)
+        | Cell::PressurePlate(id) => format!("{} {}", cell.kind(), id),
+        _ => cell.name(),
+    };
+    Some((name, suffix))
+}
+
+fn distance(cells: i8) -> String {
+    match cells {
+        1 => String::new(),
+        _ => format!(" {} cells", cells),
+    }
+}
+
+/// The first thing in every direction, like "wall north, enemy 2 cells east moving left".
+/// With limited visibility nothing is said about what the player can't see.
+pub fn surroundings(level: &Level) -> String {
+    let player = match level.player_position() {
+        Some(player) => player,
+        None => return String::new(),
+    };
+    let visible = level.visible_cells();
+    let mut parts = vec![];
+    for (direction, name) in DIRECTIONS {
+        let mut point = neighbour(player, direction);
+        let mut cells = 1;
+        let part = loop {
+            if visible
+                .as_ref()
+                .is_some_and(|visible| !visible.contains(&point))
+                && level.data.contains_key(&point)
+            {
+                break format!("darkness{} {}", distance(cells), name);
+            }
+            match level.data.get(&point) {
+                None => break format!("edge{} {}", distance(cells), name),
+                Some(&cell) => {
+                    if let Some((thing, suffix)) = cell_name(cell) {
+                        break format!("{}{} {}{}", thing, distance(cells), name, suffix);
+                    }
+                }
+            }
+            point = neighbour(point, direction);
+            cells += 1;
+        };
+        parts.push(part);
+    }
+    parts.join(", ")
+}
+
+/// Said when a level starts and when the player asks for it with the help key.
+pub fn status(
+    level: &Level,
+    level_number: usize,
+    info: &LevelInfo,
+    elapsed_time: u128,
+    inventory: Inventory,
+    lives: Option<u8>,
+) -> String {
+    let mut text = format!("Level {}", level_number);
+    if !info.name.is_empty() {
+        text.push_str(&format!(", {}", info.name));
+    }
+    text.push_str(&format!(", time {} seconds", elapsed_time));
+    if let Some(lives) = lives {
+        text.push_str(&format!(", {} lives", lives));
+    }
+    if !inventory.is_empty() {
+        text.push_str(", keys:");
+        for color in KeyColor::all() {
+            for _ in 0..inventory.count(color) {
+                text.push(' ');
+                text.push_str(color.name());
+            }
+        }
+    }
+    if let (Some(player), Some(exit)) = (level.player_position(), level.finish_position()) {
+        let mut offsets = vec![];
+        if exit.y != player.y {
+            let name = if exit.y < player.y { "north" } else { "south" };
+            offsets.push(format!("{} {}", (exit.y - player.y).abs(), name));
+        }
+        if exit.x != player.x {
+            let name = if exit.x < player.x { "west" } else { "east" };
+            offsets.push(format!("{} {}", (exit.x - player.x).abs(), name));
+        }
+        if !offsets.is_empty() {
+            text.push_str(&format!(", exit {}", offsets.join(" and ")));
+        }
+    }
+    text
+}
+
+/// What the changes are told from, taken every frame instead of a copy of the whole level.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Snapshot {
+    closed_doors: BTreeSet<u8>,
+    open_doors: BTreeSet<u8>,
+    player: Option<Point>,
+    powerup: Powerup,
+    // keys and locked doors, the ones the player can take away
+    items: BTreeMap<Point, Cell>,
+    enemies: usize,
+    message: Option<String>,
+}
+
+impl Snapshot {
+    pub fn new(level: &Level) -> Self {
+        let mut snapshot = Snapshot {
+            closed_doors: BTreeSet::new(),
+            open_doors: BTreeSet::new(),
+            player: level.player_position(),
+            powerup: Powerup::None,
+            items: BTreeMap::new(),
+            enemies: 0,
+            message: level.message().map(String::from),
+        };
+        for (&point, &cell) in &level.data {
+            match cell {
+                Cell::Player(powerup) => snapshot.powerup = powerup,
+                Cell::Key(_) | Cell::LockedDoor(_) => {
+                    snapshot.items.insert(point, cell);
+                }
+                Cell::CounterClockwiseEnemy(_) => snapshot.enemies += 1,
+                _ => {}
+            }
+        }
+        for cell in level.data.values().chain(level.floor.values()) {
+            match cell {
+                Cell::Door(id) => {
+                    snapshot.closed_doors.insert(*id);
+                }
+                Cell::OpenDoor(id) => {
+                    snapshot.open_doors.insert(*id);
+                }
+                _ => {}
+            }
+        }
+        snapshot
+    }
+}
+
+/// Everything worth saying that happened between two states of the same level,
+/// like "door 2 opened" or "invincibility: 3 moves left".
+pub fn changes(before: &Snapshot, after: &Snapshot) -> Vec<String> {
+    let mut lines = vec![];
+    for id in before.closed_doors.difference(&after.closed_doors) {
+        lines.push(format!("door {} opened", id));
+    }
+    for id in before.open_doors.difference(&after.open_doors) {
+        if after.closed_doors.contains(id) {
+            lines.push(format!("door {} closed", id));
+        }
+    }
+    if let Some(player) = after.player {
+        if before.player != Some(player) {
+            match before.items.get(&player) {
+                Some(Cell::Key(color)) => lines.push(format!("picked up {} key", color.name())),
+                Some(Cell::LockedDoor(color)) => {
+                    lines.push(format!("unlocked {} door", color.name()))
+                }
+                _ => {}
+            }
+        }
+        match (before.powerup, after.powerup) {
+            (before, Powerup::Invincible(moves)) if before != Powerup::Invincible(moves) => {
+                lines.push(format!("invincibility: {} moves left", moves))
+            }
+            (Powerup::Invincible(_), Powerup::None) => {
+                lines.push(String::from("invincibility is over"))
+            }
+            _ => {}
+        }
+    }
+    match after.enemies.cmp(&before.enemies) {
+        std::cmp::Ordering::Greater => lines.push(String::from("an enemy appeared")),
+        std::cmp::Ordering::Less if after.player.is_some() => {
+            lines.push(String::from("an enemy is gone"))
+        }
+        _ => {}
+    }
+    if let Some(message) = &after.message {
+        if after.message != before.message {
+            lines.push(message.clone());
+        }
+    }
+    lines
+}
+
+/// The menu, all of it when the screen is new, afterwards only the entry moved to.
+pub fn menu(menu: &Menu, progress: &Progress, whole: bool) -> Vec<String> {
+    let (title, entries) = menu_entries(menu, progress);
+    let selected = entries.get(menu.selected()).cloned().unwrap_or_default();
+    if !whole {
+        return vec![selected];
+    }
+    let mut lines = vec![String::from(title)];
+    lines.extend(entries);
+    lines.push(format!("Selected: {}", selected));
+    lines.push(String::from(MENU_KEYS));
+    lines
+}
+
+fn result(name: String, stats: &LevelStats, score: u32) -> String {
+    let played = match stats.unfinished && stats.level_number > 0 {
+        true => String::from("not finished"),
+        false => format!("time {}, {} moves", format_time(stats.time), stats.moves),
+    };
+    format!(
+        "{}: {}, {} deaths, {} restarts, {} pickups, {} hints, score {}",
+        name, played, stats.deaths, stats.restarts, stats.pickups, stats.hints, score
+    )
+}
+
+/// The results of a run, a sentence for each level played and one for the totals.
+pub fn results(stats: &[LevelStats]) -> Vec<String> {
+    let mut lines: Vec<String> = stats
+        .iter()
+        .map(|level| {
+            result(
+                format!("Level {}", level.level_number),
+                level,
+                level.score(),
+            )
+        })
+        .collect();
+    lines.push(result(
+        String::from("Total"),
+        &totals(stats),
+        total_score(stats),
+    ));
+    lines.push(String::from("Press any key to continue"));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changes;
+    use super::menu;
+    use super::results;
+    use super::status;
+    use super::surroundings;
+    use super::Snapshot;
+    use crate::utils::cell::Cell;
+    use crate::utils::direction::Direction;
+    use crate::utils::inventory::Inventory;
+    use crate::utils::key_color::KeyColor;
+    use crate::utils::level::Level;
+    use crate::utils::level_info::LevelInfo;
+    use crate::utils::menu::Menu;
+    use crate::utils::point::Point;
+    use crate::utils::powerup::Powerup;
+    use crate::utils::progress::Progress;
+    use crate::utils::stats::LevelStats;
+
+    #[test]
+    fn describe_surroundings() {
+        //  -
+        // .@.<
+        //  D
+        let mut level = Level::empty(4, 3);
+        level.update(Point { x: 1, y: 1 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::HorizontalWall);
+        level.update(
+            Point { x: 3, y: 1 },
+            Cell::CounterClockwiseEnemy(Direction::Left),
+        );
+        level.update(Point { x: 1, y: 2 }, Cell::Door(2));
+        assert_eq!(
+            surroundings(&level),
+            "wall north, enemy 2 cells east moving left, door 2 south, edge 2 cells west"
+        );
+    }
+
+    #[test]
+    fn describe_surroundings_in_the_dark() {
+        // @...X
+        let mut level = Level::empty(5, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 4, y: 0 }, Cell::Exit);
+        assert!(surroundings(&level).contains("exit 4 cells east"));
+        level.set_visibility(Some(2));
+        assert!(surroundings(&level).contains("darkness 3 cells east"));
+    }
+
+    #[test]
+    fn describe_status() {
+        // @.
+        // .X
+        let mut level = Level::empty(2, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 1 }, Cell::Exit);
+        let mut inventory = Inventory::new();
+        inventory.add(KeyColor::Red);
+        assert_eq!(
+            status(
+                &level,
+                2,
+                &LevelInfo::new("The Switch"),
+                12,
+                inventory,
+                Some(3)
+            ),
+            "Level 2, The Switch, time 12 seconds, 3 lives, keys: red, exit 1 south and 1 east"
+        );
+    }
+
+    #[test]
+    fn describe_changes() {
+        // @SD
+        let mut before = Level::empty(3, 1);
+        before.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        before.update(Point { x: 1, y: 0 }, Cell::ToggleSwitch(2));
+        before.update(Point { x: 2, y: 0 }, Cell::Door(2));
+        let mut after = before.clone();
+        let mut inventory = Inventory::new();
+        after.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        let (before, after_snapshot) = (Snapshot::new(&before), Snapshot::new(&after));
+        assert_eq!(changes(&before, &after_snapshot), vec!["door 2 opened"]);
+        assert!(changes(&after_snapshot, &after_snapshot).is_empty());
+        let mut back = after.clone();
+        back.move_player(
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        back.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(
+            changes(&after_snapshot, &Snapshot::new(&back)),
+            vec!["door 2 closed"]
+        );
+    }
+
+    #[test]
+    fn describe_invincibility() {
+        // @o.
+        let mut before = Level::empty(3, 1);
+        before.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        before.update(Point { x: 1, y: 0 }, Cell::Invincibility);
+        let mut after = before.clone();
+        let mut inventory = Inventory::new();
+        after.move_player(
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            2,
+            0,
+            &mut inventory,
+        );
+        assert_eq!(
+            changes(&Snapshot::new(&before), &Snapshot::new(&after)),
+            vec!["invincibility: 5 moves left"]
+        );
+    }
+
+    #[test]
+    fn describe_menu() {
+        let main = Menu::new(3, Some(3));
+        let lines = menu(&main, &Progress::new(), true);
+        assert_eq!(lines[0], "Diamand Duo");
+        assert!(lines.contains(&String::from("Selected: Play")));
+        assert_eq!(menu(&main, &Progress::new(), false), vec!["Play"]);
+    }
+
+    #[test]
+    fn describe_results() {
+        let stats = [
+            LevelStats {
+                time: 1_500,
+                moves: 4,
+                ..LevelStats::new(1)
+            },
+            LevelStats {
+                deaths: 2,
+                unfinished: true,
+                ..LevelStats::new(2)
+            },
+        ];
+        let lines = results(&stats);
+        assert!(lines[0].starts_with("Level 1: time 00:01.500, 4 moves, 0 deaths"));
+        assert!(lines[1].starts_with("Level 2: not finished, 2 deaths"));
+        assert!(lines[1].ends_with("score 0"));
+        assert!(lines[2].starts_with("Total: "));
+        assert_eq!(lines[3], "Press any key to continue");
+    }
+}
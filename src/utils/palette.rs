@@ -0,0 +1,157 @@
+use crate::utils::key_color::KeyColor;
+use crossterm::style::Color;
+
+/// Colors of the cells on the map. Besides the default colors there are palettes
+/// for the common color vision deficiencies, chosen with `--palette`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Palette {
+    pub empty: Color,
+    pub player: Color,
+    pub enemy: Color,
+    /// background of the walls
+    pub wall: Color,
+    pub door: Color,
+    /// switches, toggle switches and pressure plates
+    pub switch: Color,
+    pub crate_box: Color,
+    /// background of the ice
+    pub ice: Color,
+    pub conveyor: Color,
+    pub teleporter: Color,
+    pub breakable_ground: Color,
+    /// background of the invincibility candy
+    pub candy: Color,
+    pub sign: Color,
+    /// keys and locked doors, in the order of `KeyColor::all`
+    pub keys: [Color; 4],
+}
+
+pub const PALETTE_NAMES: [&str; 4] = ["default", "deuteranopia", "protanopia", "tritanopia"];
+
+impl Palette {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Palette::default()),
+            // both are red-green deficiencies, the same colors work for them
+            "deuteranopia" | "protanopia" => Some(Palette::red_green()),
+            "tritanopia" => Some(Palette::blue_yellow()),
+            _ => None,
+        }
+    }
+
+    // Blue and orange instead of red and green.
+    fn red_green() -> Self {
+        Palette {
+            empty: Color::DarkGrey,
+            player: Color::Yellow,
+            enemy: Color::Blue,
+            wall: Color::DarkBlue,
+            door: Color::DarkYellow,
+            switch: Color::Cyan,
+            crate_box: Color::Grey,
+            ice: Color::DarkCyan,
+            conveyor: Color::Magenta,
+            teleporter: Color::White,
+            breakable_ground: Color::Grey,
+            candy: Color::Yellow,
+            sign: Color::White,
+            keys: [Color::DarkYellow, Color::Cyan, Color::Blue, Color::Yellow],
+        }
+    }
+
+    // Red and cyan instead of blue and yellow.
+    fn blue_yellow() -> Self {
+        Palette {
+            empty: Color::DarkGrey,
+            player: Color::Red,
+            enemy: Color::Cyan,
+            wall: Color::DarkGrey,
+            door: Color::Magenta,
+            switch: Color::Green,
+            crate_box: Color::Grey,
+            ice: Color::DarkCyan,
+            conveyor: Color::White,
+            teleporter: Color::DarkRed,
+            breakable_ground: Color::Grey,
+            candy: Color::Red,
+            sign: Color::White,
+            keys: [Color::Red, Color::Green, Color::Cyan, Color::White],
+        }
+    }
+
+    pub fn key(&self, color: KeyColor) -> Color {
+        match color {
+            KeyColor::Red => self.keys[0],
+            KeyColor::Green => self.keys[1],
+            KeyColor::Blue => self.keys[2],
+            KeyColor::Yellow => self.keys[3],
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            empty: Color::Blue,
+            player: Color::Red,
+            enemy: Color::DarkRed,
+            wall: Color::Red,
+            door: Color::Red,
+            switch: Color::Green,
+            crate_box: Color::DarkYellow,
+            ice: Color::Cyan,
+            conveyor: Color::Magenta,
+            teleporter: Color::DarkBlue,
+            breakable_ground: Color::Grey,
+            candy: Color::Yellow,
+            sign: Color::Yellow,
+            keys: [Color::Red, Color::Green, Color::Blue, Color::Yellow],
+        }
+    }
+}
+
+/// Characters of the cells on the map. With `Shapes` (`--shapes`) every kind of cell
+/// can be told apart without any color: keys and locked doors show their color as a letter.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Glyphs {
+    Default,
+    Shapes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Palette;
+    use super::PALETTE_NAMES;
+    use crate::utils::key_color::KeyColor;
+    use crossterm::style::Color;
+
+    #[test]
+    fn from_name() {
+        for name in PALETTE_NAMES {
+            assert!(Palette::from_name(name).is_some());
+        }
+        assert_eq!(Palette::from_name("default"), Some(Palette::default()));
+        assert_eq!(
+            Palette::from_name("protanopia"),
+            Palette::from_name("deuteranopia")
+        );
+        assert_eq!(Palette::from_name("sepia"), None);
+    }
+
+    #[test]
+    fn player_and_enemy_differ() {
+        for name in PALETTE_NAMES {
+            let palette = Palette::from_name(name).unwrap();
+            assert_ne!(palette.player, palette.enemy);
+            assert_ne!(palette.door, palette.switch);
+        }
+        // no red next to green for red-green deficiencies
+        let palette = Palette::from_name("deuteranopia").unwrap();
+        assert!(
+            ![palette.player, palette.enemy, palette.door, palette.switch]
+                .iter()
+                .any(|color| matches!(color, Color::Red | Color::DarkRed | Color::Green))
+        );
+        assert_eq!(palette.key(KeyColor::Green), Color::Cyan);
+    }
+}
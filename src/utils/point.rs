@@ -1,3 +1,4 @@
+/// A position on a level, (0, 0) is the top left corner.
 #[derive(Clone, PartialEq, Copy, Eq, Hash, Debug, Ord, PartialOrd)]
 pub struct Point {
     pub x: i8,
@@ -1,5 +1,7 @@
+/// What the player currently has on, shown on its cell.
 #[derive(Clone, PartialEq, Copy, Eq, Hash, Debug)]
 pub enum Powerup {
     None,
+    /// enemies can be eaten and the void crossed for the given number of moves
     Invincible(u8),
 }
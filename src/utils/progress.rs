@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+/// Where the progress is kept, in the directory the game is started from.
+pub const SAVE_FILE: &str = "didu.save";
+
+/// Unlocked levels and best times, kept between two runs of the game.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Progress {
+    // number of unlocked levels, the first one is always unlocked
+    unlocked: usize,
+    // best time in ms by level index
+    best_times: HashMap<usize, u128>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Progress {
+            unlocked: 1,
+            best_times: HashMap::new(),
+        }
+    }
+
+    pub fn unlocked(&self) -> usize {
+        self.unlocked
+    }
+
+    pub fn is_unlocked(&self, level_index: usize) -> bool {
+        level_index < self.unlocked
+    }
+
+    pub fn best_time(&self, level_index: usize) -> Option<u128> {
+        self.best_times.get(&level_index).cloned()
+    }
+
+    /// A finished level unlocks the next one
+    pub fn record(&mut self, level_index: usize, time: u128) {
+        let best = self.best_times.entry(level_index).or_insert(time);
+        if time < *best {
+            *best = time;
+        }
+        self.unlocked = self.unlocked.max(level_index + 2);
+    }
+
+    /// Read `unlocked = <n>` and `best.<level number> = <ms>` lines
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut progress = Progress::new();
+        for line in contents.lines().map(|line| line.trim()) {
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || format!("Invalid line in {}: {}", SAVE_FILE, line);
+            let (key, value) = line
+                .split_once('=')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(invalid)?;
+            match key.split_once('.') {
+                None if key == "unlocked" => {
+                    progress.unlocked = value.parse::<usize>().map_err(|_| invalid())?.max(1)
+                }
+                Some(("best", level_number)) => {
+                    let level_number = level_number.parse::<usize>().map_err(|_| invalid())?;
+                    let time = value.parse::<u128>().map_err(|_| invalid())?;
+                    if level_number == 0 {
+                        return Err(invalid());
+                    }
+                    progress.best_times.insert(level_number - 1, time);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(progress)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut contents = format!("unlocked = {}\n", self.unlocked);
+        let mut level_indexes: Vec<&usize> = self.best_times.keys().collect();
+        level_indexes.sort();
+        for level_index in level_indexes {
+            contents.push_str(&format!(
+                "best.{} = {}\n",
+                level_index + 1,
+                self.best_times[level_index]
+            ));
+        }
+        contents
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progress;
+    #[test]
+    fn new() {
+        let progress = Progress::new();
+        assert_eq!(progress.unlocked(), 1);
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+        assert_eq!(progress.best_time(0), None);
+    }
+
+    #[test]
+    fn record() {
+        let mut progress = Progress::new();
+        progress.record(0, 500);
+        assert!(progress.is_unlocked(1));
+        assert_eq!(progress.best_time(0), Some(500));
+        progress.record(0, 700);
+        assert_eq!(progress.best_time(0), Some(500));
+        progress.record(0, 300);
+        assert_eq!(progress.best_time(0), Some(300));
+        assert_eq!(progress.unlocked(), 2);
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let mut progress = Progress::new();
+        progress.record(0, 1234);
+        progress.record(1, 42);
+        let contents = progress.to_file_string();
+        assert_eq!(contents, "unlocked = 3\nbest.1 = 1234\nbest.2 = 42\n");
+        assert_eq!(Progress::parse(&contents), Ok(progress));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Progress::parse("unlocked = many").is_err());
+        assert!(Progress::parse("best.0 = 10").is_err());
+        assert!(Progress::parse("best.x = 10").is_err());
+        assert!(Progress::parse("worst.1 = 10").is_err());
+        assert!(Progress::parse("unlocked").is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use crate::utils::clock::Clock;
+use std::io::Result;
+use std::io::Write;
+use std::time::Duration;
+
+// Recordings are written in the asciicast v2 format of asciinema:
+// a json header line followed by one `[time, "o", data]` line for every flush.
+// They can be played back with `asciinema play <file.cast>`.
+
+pub fn is_cast_file(path: &str) -> bool {
+    path.ends_with(".cast")
+}
+
+// Json string with everything below a space escaped, the terminal output is full of escape codes.
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                json.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+pub fn header(width: u16, height: u16, timestamp: u64) -> String {
+    format!(
+        "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}, \"title\": \"Diamand Duo\"}}\n",
+        width, height, timestamp
+    )
+}
+
+/// Output of the terminal `seconds` after the start of the recording
+pub fn event(seconds: f64, data: &str) -> String {
+    format!("[{:.6}, \"o\", {}]\n", seconds, json_string(data))
+}
+
+/// Passes everything on to `inner` and writes a copy of it, with the time of `clock` since the
+/// start, to `cast`. Wrap the writer of `Drawing` in it to record a session.
+pub struct Recorder<W: Write, C: Write, K: Clock> {
+    inner: W,
+    cast: C,
+    clock: K,
+    start: Duration,
+    // written since the last flush, one event is written per flush
+    pending: Vec<u8>,
+}
+
+impl<W: Write, C: Write, K: Clock> Recorder<W, C, K> {
+    pub fn new(
+        inner: W,
+        mut cast: C,
+        clock: K,
+        width: u16,
+        height: u16,
+        timestamp: u64,
+    ) -> Result<Self> {
+        cast.write_all(header(width, height, timestamp).as_bytes())?;
+        Ok(Recorder {
+            inner,
+            cast,
+            start: clock.now(),
+            clock,
+            pending: vec![],
+        })
+    }
+}
+
+impl<W: Write, C: Write, K: Clock> Write for Recorder<W, C, K> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.pending.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        // a character split between two flushes waits for the rest of it
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if valid == 0 {
+            return Ok(());
+        }
+        let data: Vec<u8> = self.pending.drain(..valid).collect();
+        let seconds = (self.clock.now() - self.start).as_secs_f64();
+        self.cast
+            .write_all(event(seconds, &String::from_utf8_lossy(&data)).as_bytes())?;
+        self.cast.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::event;
+    use super::header;
+    use super::is_cast_file;
+    use super::Recorder;
+    use crate::utils::clock::ManualClock;
+    use std::io::Write;
+    use std::time::Duration;
+
+    #[test]
+    fn cast_file() {
+        assert!(is_cast_file("session.cast"));
+        assert!(!is_cast_file("session.json"));
+    }
+
+    #[test]
+    fn header_line() {
+        assert_eq!(
+            header(80, 24, 1700000000),
+            "{\"version\": 2, \"width\": 80, \"height\": 24, \"timestamp\": 1700000000, \"title\": \"Diamand Duo\"}\n"
+        );
+    }
+
+    #[test]
+    fn escaped_event() {
+        assert_eq!(
+            event(1.5, "\u{1b}[1;1H\"@\"\\\r\n"),
+            "[1.500000, \"o\", \"\\u001b[1;1H\\\"@\\\"\\\\\\r\\n\"]\n"
+        );
+    }
+
+    #[test]
+    fn tees_output() {
+        let mut cast = vec![];
+        let mut terminal = vec![];
+        {
+            let clock = ManualClock::new();
+            // the recording starts at its own zero
+            clock.advance(Duration::from_secs(3));
+            let mut recorder =
+                Recorder::new(&mut terminal, &mut cast, clock.clone(), 80, 24, 0).unwrap();
+            clock.advance(Duration::from_millis(1500));
+            write!(recorder, "@..").unwrap();
+            write!(recorder, "X").unwrap();
+            recorder.flush().unwrap();
+            // nothing new, no event
+            recorder.flush().unwrap();
+            recorder.write_all(&"░".as_bytes()[..1]).unwrap();
+            recorder.flush().unwrap();
+            clock.advance(Duration::from_millis(250));
+            recorder.write_all(&"░".as_bytes()[1..]).unwrap();
+            recorder.flush().unwrap();
+        }
+        assert_eq!(String::from_utf8(terminal).unwrap(), "@..X░");
+        let cast = String::from_utf8(cast).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"version\": 2"));
+        assert_eq!(lines[1], "[1.500000, \"o\", \"@..X\"]");
+        assert_eq!(lines[2], "[1.750000, \"o\", \"░\"]");
+    }
+}
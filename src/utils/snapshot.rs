@@ -0,0 +1,475 @@
+use crate::utils::cell::Cell;
+use crate::utils::drawing::cell_style;
+use crate::utils::drawing::shown_cell;
+use crate::utils::drawing::CellStyle;
+use crate::utils::level::Level;
+use crate::utils::palette::Glyphs;
+use crate::utils::palette::Palette;
+use crate::utils::point::Point;
+use crossterm::style::Color;
+
+/// Written by the "x" key while playing, with the extension of the format.
+pub const SNAPSHOT_FILE: &str = "didu-snapshot";
+
+// Size of a cell in the svg, in pixels
+const CELL_WIDTH: usize = 12;
+const CELL_HEIGHT: usize = 20;
+
+/// File formats a level can be rendered to, chosen by the file extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SnapshotFormat {
+    Svg,
+    Html,
+}
+
+impl SnapshotFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        if path.ends_with(".svg") {
+            Some(SnapshotFormat::Svg)
+        } else if path.ends_with(".html") {
+            Some(SnapshotFormat::Html)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SnapshotFormat::Svg => "svg",
+            SnapshotFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SnapshotOptions {
+    pub palette: Palette,
+    pub glyphs: Glyphs,
+    /// list every kind of cell on the map below it
+    pub legend: bool,
+    /// number the rows and columns
+    pub grid: bool,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        SnapshotOptions {
+            palette: Palette::default(),
+            glyphs: Glyphs::Default,
+            legend: false,
+            grid: false,
+        }
+    }
+}
+
+// The same colors a terminal with the default xterm colors shows.
+fn css_color(color: Color) -> String {
+    let hex = match color {
+        Color::Black => "#000000",
+        Color::DarkRed => "#cd0000",
+        Color::DarkGreen => "#00cd00",
+        Color::DarkYellow => "#cdcd00",
+        Color::DarkBlue => "#0000ee",
+        Color::DarkMagenta => "#cd00cd",
+        Color::DarkCyan => "#00cdcd",
+        Color::Grey => "#e5e5e5",
+        Color::DarkGrey => "#7f7f7f",
+        Color::Red => "#ff0000",
+        Color::Green => "#00ff00",
+        Color::Yellow => "#ffff00",
+        Color::Blue => "#5c5cff",
+        Color::Magenta => "#ff00ff",
+        Color::Cyan => "#00ffff",
+        Color::White | Color::Reset => "#ffffff",
+        Color::Rgb { r, g, b } => return format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::AnsiValue(value) => return ansi_color(value),
+    };
+    String::from(hex)
+}
+
+// The 256 color palette: the 16 named colors, a 6x6x6 color cube and 24 greys.
+fn ansi_color(value: u8) -> String {
+    let named = [
+        Color::Black,
+        Color::DarkRed,
+        Color::DarkGreen,
+        Color::DarkYellow,
+        Color::DarkBlue,
+        Color::DarkMagenta,
+        Color::DarkCyan,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+    match value {
+        0..=15 => css_color(named[value as usize]),
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + 40 * v };
+            let index = value - 16;
+            css_color(Color::Rgb {
+                r: level(index / 36),
+                g: level(index / 6 % 6),
+                b: level(index % 6),
+            })
+        }
+        _ => {
+            let grey = 8 + 10 * (value - 232);
+            css_color(Color::Rgb {
+                r: grey,
+                g: grey,
+                b: grey,
+            })
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// The shown cells of the level by row, None for the ones the player has not seen yet.
+fn rows(level: &Level) -> Vec<Vec<Option<(Cell, bool)>>> {
+    let (max_x, max_y) = level.size();
+    let visible = level.visible_cells();
+    (0..=max_y)
+        .map(|y| {
+            (0..=max_x)
+                .map(|x| shown_cell(level, &visible, Point { x, y }))
+                .collect()
+        })
+        .collect()
+}
+
+// Every kind of cell on the map once, with its name
+fn legend(level: &Level, options: &SnapshotOptions) -> Vec<(CellStyle, String)> {
+    let mut entries: Vec<(CellStyle, String)> = vec![];
+    for (cell, _) in rows(level).into_iter().flatten().flatten() {
+        let style = cell_style(cell, &options.palette, options.glyphs);
+        let name = cell.name();
+        if !entries
+            .iter()
+            .any(|(known, known_name)| known.glyph == style.glyph && *known_name == name)
+        {
+            entries.push((style, name));
+        }
+    }
+    entries
+}
+
+fn title(level: &Level) -> String {
+    match level.info().name.as_str() {
+        "" => String::from("Diamand Duo"),
+        name => format!("Diamand Duo - {}", name),
+    }
+}
+
+pub fn snapshot(level: &Level, format: SnapshotFormat, options: &SnapshotOptions) -> String {
+    match format {
+        SnapshotFormat::Svg => to_svg(level, options),
+        SnapshotFormat::Html => to_html(level, options),
+    }
+}
+
+fn svg_cell(style: &CellStyle, x: usize, y: usize, remembered: bool) -> String {
+    let mut svg = String::new();
+    if let Some(background) = style.background {
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            x,
+            y,
+            CELL_WIDTH,
+            CELL_HEIGHT,
+            css_color(background)
+        ));
+    }
+    if !style.glyph.trim().is_empty() {
+        let opacity = if remembered { " opacity=\"0.5\"" } else { "" };
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" fill=\"{}\"{}>{}</text>\n",
+            x + CELL_WIDTH / 2,
+            y + CELL_HEIGHT - 5,
+            css_color(style.foreground),
+            opacity,
+            escape(&style.glyph)
+        ));
+    }
+    svg
+}
+
+/// A standalone svg with one text element per cell.
+pub fn to_svg(level: &Level, options: &SnapshotOptions) -> String {
+    let rows = rows(level);
+    let columns = rows.first().map_or(0, |row| row.len());
+    // room for the row numbers on the left and the column numbers on top
+    let (left, top) = if options.grid {
+        (3 * CELL_WIDTH, CELL_HEIGHT)
+    } else {
+        (0, 0)
+    };
+    let legend = if options.legend {
+        legend(level, options)
+    } else {
+        vec![]
+    };
+    let legend_width = legend
+        .iter()
+        .map(|(_, name)| (name.len() + 4) * CELL_WIDTH)
+        .max()
+        .unwrap_or(0);
+    let width = (left + columns * CELL_WIDTH).max(legend_width);
+    let map_height = top + rows.len() * CELL_HEIGHT;
+    let height = match legend.len() {
+        0 => map_height,
+        entries => map_height + (entries + 1) * CELL_HEIGHT,
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\" font-size=\"16\" text-anchor=\"middle\">\n",
+        width, height
+    );
+    svg.push_str(&format!("<title>{}</title>\n", escape(&title(level))));
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>\n");
+    for (y, row) in rows.iter().enumerate() {
+        for (x, shown) in row.iter().enumerate() {
+            if let Some((cell, remembered)) = shown {
+                let style = cell_style(*cell, &options.palette, options.glyphs);
+                svg.push_str(&svg_cell(
+                    &style,
+                    left + x * CELL_WIDTH,
+                    top + y * CELL_HEIGHT,
+                    *remembered,
+                ));
+            }
+        }
+    }
+    if options.grid {
+        let grey = css_color(Color::DarkGrey);
+        for x in 0..columns {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n",
+                left + x * CELL_WIDTH + CELL_WIDTH / 2,
+                CELL_HEIGHT - 5,
+                grey,
+                x % 10
+            ));
+        }
+        for y in 0..rows.len() {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\" text-anchor=\"end\">{}</text>\n",
+                left - CELL_WIDTH / 2,
+                top + (y + 1) * CELL_HEIGHT - 5,
+                grey,
+                y
+            ));
+        }
+        svg.push_str(&format!(
+            "<g stroke=\"{}\" stroke-width=\"0.5\">\n",
+            css_color(Color::DarkGrey)
+        ));
+        for x in 0..=columns {
+            let line_x = left + x * CELL_WIDTH;
+            svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+                line_x, top, line_x, map_height
+            ));
+        }
+        for y in 0..=rows.len() {
+            let line_y = top + y * CELL_HEIGHT;
+            svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+                left,
+                line_y,
+                left + columns * CELL_WIDTH,
+                line_y
+            ));
+        }
+        svg.push_str("</g>\n");
+    }
+    for (i, (style, name)) in legend.iter().enumerate() {
+        let y = map_height + (i + 1) * CELL_HEIGHT;
+        svg.push_str(&svg_cell(style, 0, y, false));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" fill=\"#ffffff\" text-anchor=\"start\">= {}</text>\n",
+            2 * CELL_WIDTH,
+            y + CELL_HEIGHT - 5,
+            escape(name)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn html_cell(style: &CellStyle, remembered: bool) -> String {
+    let mut css = format!("color: {}", css_color(style.foreground));
+    if let Some(background) = style.background {
+        css.push_str(&format!("; background: {}", css_color(background)));
+    }
+    if remembered {
+        css.push_str("; opacity: 0.5");
+    }
+    format!("<span style=\"{}\">{}</span>", css, escape(&style.glyph))
+}
+
+/// A standalone html page with the map as preformatted text.
+pub fn to_html(level: &Level, options: &SnapshotOptions) -> String {
+    let rows = rows(level);
+    let columns = rows.first().map_or(0, |row| row.len());
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\npre {{ display: inline-block; padding: 1em; background: #000000; \
+         color: #ffffff; font-family: monospace; line-height: 1.2; }}\n\
+         .grid {{ color: {}; }}\n</style>\n</head>\n<body>\n<pre>\n",
+        escape(&title(level)),
+        css_color(Color::DarkGrey)
+    );
+    if options.grid {
+        let numbers: String = (0..columns).map(|x| (x % 10).to_string()).collect();
+        html.push_str(&format!("<span class=\"grid\">   {}</span>\n", numbers));
+    }
+    for (y, row) in rows.iter().enumerate() {
+        if options.grid {
+            html.push_str(&format!("<span class=\"grid\">{:>2} </span>", y));
+        }
+        for shown in row {
+            match shown {
+                Some((cell, remembered)) => html.push_str(&html_cell(
+                    &cell_style(*cell, &options.palette, options.glyphs),
+                    *remembered,
+                )),
+                None => html.push(' '),
+            }
+        }
+        html.push('\n');
+    }
+    if options.legend {
+        html.push('\n');
+        for (style, name) in legend(level, options) {
+            html.push_str(&format!(
+                "{} = {}\n",
+                html_cell(&style, false),
+                escape(&name)
+            ));
+        }
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::css_color;
+    use super::snapshot;
+    use super::SnapshotFormat;
+    use super::SnapshotOptions;
+    use crate::utils::cell::Cell;
+    use crate::utils::direction::Direction;
+    use crate::utils::level::Level;
+    use crate::utils::palette::Glyphs;
+    use crate::utils::point::Point;
+    use crate::utils::powerup::Powerup;
+    use crossterm::style::Color;
+
+    fn level() -> Level {
+        // @<X
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Conveyor(Direction::Left));
+        level.update(Point { x: 2, y: 0 }, Cell::Exit);
+        level
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            SnapshotFormat::from_path("level.svg"),
+            Some(SnapshotFormat::Svg)
+        );
+        assert_eq!(
+            SnapshotFormat::from_path("docs/level.html"),
+            Some(SnapshotFormat::Html)
+        );
+        assert_eq!(SnapshotFormat::from_path("level.png"), None);
+        assert_eq!(SnapshotFormat::Svg.extension(), "svg");
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(css_color(Color::Red), "#ff0000");
+        assert_eq!(css_color(Color::Rgb { r: 1, g: 2, b: 255 }), "#0102ff");
+        assert_eq!(css_color(Color::AnsiValue(9)), "#ff0000");
+        assert_eq!(css_color(Color::AnsiValue(16)), "#000000");
+        assert_eq!(css_color(Color::AnsiValue(231)), "#ffffff");
+        assert_eq!(css_color(Color::AnsiValue(232)), "#080808");
+    }
+
+    #[test]
+    fn svg() {
+        let svg = snapshot(&level(), SnapshotFormat::Svg, &SnapshotOptions::default());
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"36\" height=\"20\"")
+        );
+        assert!(svg.contains("<text x=\"6\" y=\"15\" fill=\"#ff0000\">@</text>"));
+        // escaped conveyor
+        assert!(svg.contains(">&lt;</text>"));
+        // the exit has a background
+        assert!(
+            svg.contains("<rect x=\"24\" y=\"0\" width=\"12\" height=\"20\" fill=\"#000000\"/>")
+        );
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(!svg.contains("<line"));
+    }
+
+    #[test]
+    fn svg_with_grid_and_legend() {
+        let options = SnapshotOptions {
+            legend: true,
+            grid: true,
+            ..SnapshotOptions::default()
+        };
+        let svg = snapshot(&level(), SnapshotFormat::Svg, &options);
+        assert!(svg.contains("<line"));
+        assert!(svg.contains("= conveyor belt</text>"));
+        assert!(svg.contains("= exit</text>"));
+    }
+
+    #[test]
+    fn html() {
+        let options = SnapshotOptions {
+            legend: true,
+            grid: true,
+            glyphs: Glyphs::Shapes,
+            ..SnapshotOptions::default()
+        };
+        let mut level = level();
+        level.update(Point { x: 1, y: 0 }, Cell::Void);
+        let html = snapshot(&level, SnapshotFormat::Html, &options);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<span class=\"grid\">   012</span>\n"));
+        assert!(html.contains(
+            "<span class=\"grid\"> 0 </span><span style=\"color: #ff0000\">@</span>\
+             <span style=\"color: #7f7f7f; background: #000000\">░</span>"
+        ));
+        assert!(html.contains("= void\n"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn hides_unexplored_cells() {
+        let mut level = level();
+        level.set_visibility(Some(1));
+        let html = snapshot(&level, SnapshotFormat::Html, &SnapshotOptions::default());
+        assert!(html.contains("@</span><span style=\"color: #ff00ff\">&lt;</span> \n"));
+        let svg = snapshot(&level, SnapshotFormat::Svg, &SnapshotOptions::default());
+        assert!(!svg.contains(">X</text>"));
+    }
+}
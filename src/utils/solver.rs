@@ -0,0 +1,542 @@
+use crate::utils::cell::Cell;
+use crate::utils::direction::Direction;
+use crate::utils::inventory::Inventory;
+use crate::utils::level::neighbour;
+use crate::utils::level::Level;
+use crate::utils::point::Point;
+use crate::utils::powerup::Powerup;
+use crate::utils::trigger::Trigger;
+use crate::utils::trigger::TriggerEvent;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+/// Default number of level states the solver looks at before giving up.
+pub const MAX_STATES: usize = 20_000;
+
+/// Smaller budget for hints, they are searched while the player waits.
+pub const HINT_STATES: usize = 5_000;
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Right,
+    Direction::Left,
+];
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Solution {
+    /// shortest sequence of moves reaching the exit
+    Path(Vec<Direction>),
+    /// every reachable state was visited without reaching the exit
+    Impossible,
+    /// gave up after looking at too many states
+    Unknown,
+}
+
+/// Advice for the player, computed from the current state of a level.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Hint {
+    /// the first move of a shortest path to the exit
+    Move(Direction),
+    /// no move leads to the exit any more
+    Unwinnable,
+    /// the solver gave up
+    Unknown,
+}
+
+// Everything a move can change, used to skip states we have already seen.
+#[derive(PartialEq, Eq, Hash)]
+struct StateKey {
+    cells: Vec<Cell>,
+    floor: Vec<(Point, Cell)>,
+    inventory: Inventory,
+    // triggers that did not fire yet, and the repeating ones
+    triggers: Vec<Trigger>,
+}
+
+fn state_key(level: &Level, points: &[Point], inventory: Inventory) -> StateKey {
+    let mut floor: Vec<(Point, Cell)> = level
+        .floor
+        .iter()
+        .map(|(&point, &cell)| (point, cell))
+        .collect();
+    floor.sort_by_key(|(point, _)| *point);
+    StateKey {
+        cells: points.iter().map(|point| level.data[point]).collect(),
+        floor,
+        inventory,
+        triggers: level.triggers().to_vec(),
+    }
+}
+
+/// Shortest way to the exit. Enemies move on their own, so they are left out
+/// and their cells are treated as empty.
+pub fn solve(level: &Level, inventory: Inventory, max_states: usize) -> Solution {
+    match level.finish_position() {
+        Some(finish) => search(level, inventory, finish, false, max_states),
+        None => Solution::Impossible,
+    }
+}
+
+/// Shortest safe way to any cell, used to move the player with the mouse.
+/// Enemies stay where they are and block the way.
+pub fn path_to(level: &Level, inventory: Inventory, target: Point, max_states: usize) -> Solution {
+    search(level, inventory, target, true, max_states)
+}
+
+// Breadth first search over the moves of the player, using the same rules as the game.
+// Moves into the void or an enemy are never taken.
+// Conveyors only move things on an enemy tick and therefore do not move anything here.
+// When enemies could hold down a pressure plate, conveyors are around or triggers wait for
+// enemies or timers, a target we can't reach is only `Unknown`, as the enemy ticks might
+// make it possible.
+fn search(
+    level: &Level,
+    inventory: Inventory,
+    target: Point,
+    keep_enemies: bool,
+    max_states: usize,
+) -> Solution {
+    if level.player_position() == Some(target) {
+        return Solution::Path(vec![]);
+    }
+    let (max_x, max_y) = level.size();
+    let mut start = level.clone();
+    let mut points: Vec<Point> = start.data.keys().cloned().collect();
+    points.sort();
+    // with what is below players, enemies and boxes, like a plate held down by an enemy
+    let cells: Vec<Cell> = points
+        .iter()
+        .map(|point| start.data[point])
+        .chain(start.floor.values().copied())
+        .collect();
+    let has = |matches: fn(&Cell) -> bool| cells.iter().any(matches);
+    let ticks_matter = has(|cell| matches!(cell, Cell::Conveyor(_)))
+        || (has(|cell| matches!(cell, Cell::CounterClockwiseEnemy(_)))
+            && has(|cell| matches!(cell, Cell::PressurePlate(_))))
+        || level.triggers().iter().any(|trigger| {
+            matches!(
+                trigger.event,
+                TriggerEvent::EnemyEntered(_) | TriggerEvent::TimerExpired(_)
+            )
+        });
+    for point in &points {
+        if let Some(Cell::CounterClockwiseEnemy(_)) = start.data.get(point) {
+            if !keep_enemies {
+                // whatever the enemy stood on comes back, a plate under it is let go
+                start.vacate(*point);
+            }
+        }
+    }
+
+    // every move leading to a new state, with the index of the move before it
+    let mut moves: Vec<(Option<usize>, Direction)> = vec![];
+    let mut seen = HashSet::new();
+    seen.insert(state_key(&start, &points, inventory));
+    let mut queue = VecDeque::new();
+    queue.push_back((start, inventory, None::<usize>));
+
+    while let Some((state, inventory, index)) = queue.pop_front() {
+        let player = match state.player_position() {
+            Some(player) => player,
+            None => continue,
+        };
+        for direction in DIRECTIONS {
+            let mut next = state.clone();
+            let mut next_inventory = inventory;
+            let new_position = neighbour(player, direction);
+            // walking into an enemy is deadly, unless the player is invincible
+            if let (Some(Cell::Player(Powerup::None)), Some(Cell::CounterClockwiseEnemy(_))) =
+                (state.data.get(&player), state.data.get(&new_position))
+            {
+                continue;
+            }
+            let entered = next.move_player(player, new_position, max_x, max_y, &mut next_inventory);
+            // the exit is never stood on, it is reached by walking into it
+            let arrived = next.player_position() == Some(target)
+                || (entered == target && level.finish_position() == Some(target));
+            if arrived && next.player_position().is_some() {
+                let mut path = vec![direction];
+                let mut current = index;
+                while let Some(i) = current {
+                    let (parent, direction) = moves[i];
+                    path.push(direction);
+                    current = parent;
+                }
+                path.reverse();
+                return Solution::Path(path);
+            }
+            // the player died
+            if next.player_position().is_none() {
+                continue;
+            }
+            let key = state_key(&next, &points, next_inventory);
+            if seen.contains(&key) {
+                continue;
+            }
+            if seen.len() >= max_states {
+                return Solution::Unknown;
+            }
+            seen.insert(key);
+            moves.push((index, direction));
+            queue.push_back((next, next_inventory, Some(moves.len() - 1)));
+        }
+    }
+    if ticks_matter {
+        Solution::Unknown
+    } else {
+        Solution::Impossible
+    }
+}
+
+/// Where the player ends up after every move of the path, to preview it.
+/// Ends with the exit when the path reaches it.
+pub fn walk(level: &Level, path: &[Direction], mut inventory: Inventory) -> Vec<Point> {
+    let mut level = level.clone();
+    let (max_x, max_y) = level.size();
+    let mut cells = vec![];
+    for direction in path {
+        if let Some(player) = level.player_position() {
+            let new_position = neighbour(player, *direction);
+            let entered = level.move_player(player, new_position, max_x, max_y, &mut inventory);
+            if level.finish_position() == Some(entered) {
+                cells.push(entered);
+                break;
+            }
+            cells.extend(level.player_position());
+        }
+    }
+    cells
+}
+
+pub fn hint(level: &Level, inventory: Inventory) -> Hint {
+    match solve(level, inventory, HINT_STATES) {
+        Solution::Path(path) => match path.first() {
+            Some(direction) => Hint::Move(*direction),
+            None => Hint::Unknown,
+        },
+        Solution::Impossible => Hint::Unwinnable,
+        Solution::Unknown => Hint::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hint;
+    use super::path_to;
+    use super::solve;
+    use super::state_key;
+    use super::walk;
+    use super::Hint;
+    use super::Solution;
+    use super::MAX_STATES;
+    use crate::levels::all;
+    use crate::utils::cell::Cell;
+    use crate::utils::direction::Direction;
+    use crate::utils::inventory::Inventory;
+    use crate::utils::key_color::KeyColor;
+    use crate::utils::level::Level;
+    use crate::utils::point::Point;
+    use crate::utils::powerup::Powerup;
+    use crate::utils::trigger::Action;
+    use crate::utils::trigger::Trigger;
+    use crate::utils::trigger::TriggerEvent;
+
+    #[test]
+    fn straight_path() {
+        // @.X
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 2, y: 0 }, Cell::Exit);
+        assert_eq!(
+            solve(&level, Inventory::new(), MAX_STATES),
+            Solution::Path(vec![Direction::Right, Direction::Right])
+        );
+    }
+
+    #[test]
+    fn switch_opens_door() {
+        // @D X
+        // S.|.
+        let mut level = Level::empty(4, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Door(0));
+        level.update(Point { x: 2, y: 0 }, Cell::Empty);
+        level.update(Point { x: 3, y: 0 }, Cell::Exit);
+        level.update(Point { x: 0, y: 1 }, Cell::Switch(0));
+        level.update(Point { x: 2, y: 1 }, Cell::VerticalWall);
+        assert_eq!(
+            solve(&level, Inventory::new(), MAX_STATES),
+            Solution::Path(vec![
+                Direction::Down,
+                Direction::Up,
+                Direction::Right,
+                Direction::Right,
+                Direction::Right
+            ])
+        );
+    }
+
+    #[test]
+    fn impossible() {
+        // @ X
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::VerticalWall);
+        level.update(Point { x: 2, y: 0 }, Cell::Exit);
+        assert_eq!(
+            solve(&level, Inventory::new(), MAX_STATES),
+            Solution::Impossible
+        );
+        // without an exit there is nothing to reach
+        assert_eq!(
+            solve(&Level::empty(2, 2), Inventory::new(), MAX_STATES),
+            Solution::Impossible
+        );
+    }
+
+    #[test]
+    fn enemy_on_pressure_plate() {
+        // @D.X
+        // ----
+        // >_..
+        let mut level = Level::empty(4, 3);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Door(0));
+        level.update(Point { x: 3, y: 0 }, Cell::Exit);
+        for x in 0..4 {
+            level.update(Point { x, y: 1 }, Cell::HorizontalWall);
+        }
+        level.update(
+            Point { x: 0, y: 2 },
+            Cell::CounterClockwiseEnemy(Direction::Right),
+        );
+        level.update(Point { x: 1, y: 2 }, Cell::PressurePlate(0));
+        assert_eq!(
+            solve(&level, Inventory::new(), MAX_STATES),
+            Solution::Unknown
+        );
+    }
+
+    #[test]
+    fn states_with_different_triggers() {
+        // @..
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        let mut points: Vec<Point> = level.data.keys().cloned().collect();
+        points.sort();
+        let entered = |x| TriggerEvent::PlayerEntered(Point { x, y: 0 });
+        let mut first = level.clone();
+        first.add_trigger(Trigger::new(entered(1), vec![Action::OpenDoors(1)]));
+        let mut second = level.clone();
+        second.add_trigger(Trigger::new(entered(2), vec![Action::OpenDoors(1)]));
+        // the same number of triggers left, but not the same ones
+        assert!(
+            state_key(&first, &points, Inventory::new())
+                != state_key(&second, &points, Inventory::new())
+        );
+        assert!(
+            state_key(&first, &points, Inventory::new())
+                == state_key(&first.clone(), &points, Inventory::new())
+        );
+    }
+
+    #[test]
+    fn hint_from_current_state() {
+        // .@X
+        // ...
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 2, y: 0 }, Cell::Exit);
+        assert_eq!(hint(&level, Inventory::new()), Hint::Move(Direction::Right));
+
+        // the player is stuck in a dead end
+        //  .X
+        // @|.
+        level.update(Point { x: 1, y: 0 }, Cell::Empty);
+        level.update(Point { x: 0, y: 1 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 1 }, Cell::VerticalWall);
+        level.update(Point { x: 0, y: 0 }, Cell::Void);
+        assert_eq!(hint(&level, Inventory::new()), Hint::Unwinnable);
+    }
+
+    #[test]
+    fn hints_for_all_levels() {
+        // the smaller budget is still enough from the start of every level
+        for level in all::levels() {
+            let name = level.info().name.as_str();
+            match hint(&level, Inventory::new()) {
+                Hint::Move(_) if name != "Under Pressure" => {}
+                Hint::Unknown if name == "Under Pressure" => {}
+                other => panic!("{}: {:?}", name, other),
+            }
+        }
+    }
+
+    #[test]
+    fn path_around_danger() {
+        // @ §.
+        // .D..
+        // ....
+        let mut level = Level::empty(4, 3);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Void);
+        level.update(
+            Point { x: 2, y: 0 },
+            Cell::CounterClockwiseEnemy(Direction::Up),
+        );
+        level.update(Point { x: 1, y: 1 }, Cell::Door(0));
+        assert_eq!(
+            path_to(&level, Inventory::new(), Point { x: 3, y: 0 }, MAX_STATES),
+            Solution::Path(vec![
+                Direction::Down,
+                Direction::Down,
+                Direction::Right,
+                Direction::Right,
+                Direction::Up,
+                Direction::Right,
+                Direction::Up
+            ])
+        );
+        // already there
+        assert_eq!(
+            path_to(&level, Inventory::new(), Point { x: 0, y: 0 }, MAX_STATES),
+            Solution::Path(vec![])
+        );
+        // the enemy itself can't be reached
+        assert_eq!(
+            path_to(&level, Inventory::new(), Point { x: 2, y: 0 }, MAX_STATES),
+            Solution::Impossible
+        );
+    }
+
+    #[test]
+    fn path_to_blocked_cells() {
+        // -@L
+        //  D
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 1, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 0, y: 0 }, Cell::HorizontalWall);
+        level.update(Point { x: 2, y: 0 }, Cell::LockedDoor(KeyColor::Red));
+        level.update(Point { x: 1, y: 1 }, Cell::Door(0));
+        // walking into them is no way there
+        for target in [
+            Point { x: 0, y: 0 },
+            Point { x: 2, y: 0 },
+            Point { x: 1, y: 1 },
+        ] {
+            assert_eq!(
+                path_to(&level, Inventory::new(), target, MAX_STATES),
+                Solution::Impossible
+            );
+        }
+        // the exit is reached by walking into it
+        level.update(Point { x: 1, y: 1 }, Cell::Exit);
+        assert_eq!(
+            path_to(&level, Inventory::new(), Point { x: 1, y: 1 }, MAX_STATES),
+            Solution::Path(vec![Direction::Down])
+        );
+    }
+
+    #[test]
+    fn walk_slides_on_ice() {
+        // @~.
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Ice);
+        assert_eq!(
+            walk(
+                &level,
+                &[Direction::Right, Direction::Left],
+                Inventory::new()
+            ),
+            vec![Point { x: 2, y: 0 }, Point { x: 0, y: 0 }]
+        );
+    }
+
+    #[test]
+    fn walk_to_the_exit() {
+        // @.X
+        let mut level = Level::empty(3, 1);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 2, y: 0 }, Cell::Exit);
+        assert_eq!(
+            walk(
+                &level,
+                &[Direction::Right, Direction::Right, Direction::Left],
+                Inventory::new()
+            ),
+            vec![Point { x: 1, y: 0 }, Point { x: 2, y: 0 }]
+        );
+    }
+
+    #[test]
+    fn enemy_leaves_the_plate() {
+        // @DX
+        // _<-
+        let mut level = Level::empty(3, 2);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 1, y: 0 }, Cell::Door(1));
+        level.update(Point { x: 2, y: 0 }, Cell::Exit);
+        level.update(Point { x: 0, y: 1 }, Cell::PressurePlate(1));
+        level.update(
+            Point { x: 1, y: 1 },
+            Cell::CounterClockwiseEnemy(Direction::Left),
+        );
+        level.update(Point { x: 2, y: 1 }, Cell::HorizontalWall);
+        // the enemy steps onto the plate and holds the door open
+        level.update_enemies();
+        assert_eq!(level.cell(Point { x: 1, y: 0 }), Some(Cell::OpenDoor(1)));
+        // without the enemy the door is closed, only waiting for it could help
+        assert_eq!(
+            solve(&level, Inventory::new(), MAX_STATES),
+            Solution::Unknown
+        );
+        assert_eq!(hint(&level, Inventory::new()), Hint::Unknown);
+    }
+
+    #[test]
+    fn gives_up() {
+        let mut level = Level::empty(8, 8);
+        level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+        level.update(Point { x: 7, y: 7 }, Cell::Exit);
+        assert_eq!(solve(&level, Inventory::new(), 3), Solution::Unknown);
+    }
+
+    #[test]
+    fn par_moves_can_be_reached() {
+        for level in all::levels() {
+            let info = level.info();
+            assert!(info.par_time.is_some(), "{}", info.name);
+            let par_moves = info.par_moves.unwrap() as usize;
+            // Under Pressure is checked by tests/game.rs, the solver can't wait for the enemy
+            if let Solution::Path(moves) = solve(&level, Inventory::new(), MAX_STATES) {
+                assert!(par_moves >= moves.len(), "{}", info.name);
+            }
+        }
+    }
+
+    #[test]
+    fn all_levels_are_solvable() {
+        for level in all::levels() {
+            let name = &level.info().name;
+            let moves = match solve(&level, Inventory::new(), MAX_STATES) {
+                Solution::Path(moves) => moves,
+                // the door only opens while the enemy stands on the plate, the solver can't
+                // wait for it. tests/game.rs plays it with the enemy moving.
+                Solution::Unknown if name == "Under Pressure" => continue,
+                solution => panic!("{}: {:?}", name, solution),
+            };
+            // like the solver, walk without the enemies in the way
+            let mut calm = level.clone();
+            for (point, cell) in level.data.iter() {
+                if let Cell::CounterClockwiseEnemy(_) = cell {
+                    calm.vacate(*point);
+                }
+            }
+            let cells = walk(&calm, &moves, Inventory::new());
+            assert_eq!(cells.len(), moves.len(), "{}", name);
+            assert_eq!(cells.last().cloned(), level.finish_position(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::utils::drawing::format_time;
+
+/// What happened while playing a single level, shown at the end of a run.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LevelStats {
+    /// 1 based, as shown in the status bar. 0 for the totals.
+    pub level_number: usize,
+    /// time and moves of the finishing attempt, both start over after dying or restarting.
+    /// time in ms, walking into a wall is no move.
+    pub time: u128,
+    pub moves: u32,
+    pub deaths: u32,
+    pub restarts: u32,
+    /// keys and powerups collected on the way to the exit
+    pub pickups: u32,
+    /// hints asked for
+    pub hints: u32,
+    /// par values of the level, see `LevelInfo`
+    pub par_time: Option<u128>,
+    pub par_moves: Option<u32>,
+    /// the level a run ended on without reaching the exit.
+    /// Only its deaths, restarts and hints count, it scores nothing.
+    pub unfinished: bool,
+}
+
+/// File formats the results can be exported to, chosen by the file extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        if path.ends_with(".csv") {
+            Some(ExportFormat::Csv)
+        } else if path.ends_with(".json") {
+            Some(ExportFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+impl LevelStats {
+    pub fn new(level_number: usize) -> Self {
+        LevelStats {
+            level_number,
+            ..LevelStats::default()
+        }
+    }
+
+    /// 1000 points for finishing, 50 for every pickup and 200 each for staying within
+    /// par time and par moves. Every second and move costs a point,
+    /// every death 100 and every restart 50. A level never scores below 0.
+    pub fn score(&self) -> u32 {
+        if self.unfinished {
+            return 0;
+        }
+        let mut bonus = 1000 + 50 * self.pickups as u128;
+        if self.par_time.is_some_and(|par_time| self.time <= par_time) {
+            bonus += 200;
+        }
+        if self
+            .par_moves
+            .is_some_and(|par_moves| self.moves <= par_moves)
+        {
+            bonus += 200;
+        }
+        let penalty = self.time / 1000
+            + self.moves as u128
+            + 100 * self.deaths as u128
+            + 50 * self.restarts as u128;
+        bonus.saturating_sub(penalty) as u32
+    }
+}
+
+/// Sum of all levels of a run. The score is the sum of the level scores.
+pub fn totals(stats: &[LevelStats]) -> LevelStats {
+    stats
+        .iter()
+        .fold(LevelStats::new(0), |total, level| LevelStats {
+            level_number: 0,
+            time: total.time + level.time,
+            moves: total.moves + level.moves,
+            deaths: total.deaths + level.deaths,
+            restarts: total.restarts + level.restarts,
+            pickups: total.pickups + level.pickups,
+            hints: total.hints + level.hints,
+            // a run that ended on a level without finishing it
+            unfinished: total.unfinished || level.unfinished,
+            ..total
+        })
+}
+
+pub fn total_score(stats: &[LevelStats]) -> u32 {
+    stats.iter().map(|level| level.score()).sum()
+}
+
+pub fn export(stats: &[LevelStats], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Csv => to_csv(stats),
+        ExportFormat::Json => to_json(stats),
+    }
+}
+
+fn csv_row(level: &str, stats: &LevelStats, score: u32) -> String {
+    format!(
+        "{},{},{},{},{},{},{},{},{},{}\n",
+        level,
+        stats.time,
+        format_time(stats.time),
+        stats.moves,
+        stats.deaths,
+        stats.restarts,
+        stats.pickups,
+        stats.hints,
+        score,
+        !stats.unfinished
+    )
+}
+
+/// One row per level and a last row with the totals.
+pub fn to_csv(stats: &[LevelStats]) -> String {
+    let mut csv =
+        String::from("level,time_ms,time,moves,deaths,restarts,pickups,hints,score,finished\n");
+    for level in stats {
+        csv.push_str(&csv_row(
+            &level.level_number.to_string(),
+            level,
+            level.score(),
+        ));
+    }
+    csv.push_str(&csv_row("total", &totals(stats), total_score(stats)));
+    csv
+}
+
+// Values are written as json already, strings with their quotes.
+fn json_object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("\"{}\": {}", name, value))
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn json_array(values: &[String]) -> String {
+    format!("[{}]", values.join(", "))
+}
+
+fn json_fields(stats: &LevelStats, score: u32) -> Vec<(&'static str, String)> {
+    vec![
+        ("time_ms", stats.time.to_string()),
+        ("time", format!("\"{}\"", format_time(stats.time))),
+        ("moves", stats.moves.to_string()),
+        ("deaths", stats.deaths.to_string()),
+        ("restarts", stats.restarts.to_string()),
+        ("pickups", stats.pickups.to_string()),
+        ("hints", stats.hints.to_string()),
+        ("score", score.to_string()),
+        ("finished", (!stats.unfinished).to_string()),
+    ]
+}
+
+/// `{"levels": [{"level": 1, ...}], "total": {...}}`
+pub fn to_json(stats: &[LevelStats]) -> String {
+    let levels: Vec<String> = stats
+        .iter()
+        .map(|level| {
+            let mut fields = vec![("level", level.level_number.to_string())];
+            fields.extend(json_fields(level, level.score()));
+            json_object(&fields)
+        })
+        .collect();
+    let total = json_object(&json_fields(&totals(stats), total_score(stats)));
+    format!(
+        "{}\n",
+        json_object(&[("levels", json_array(&levels)), ("total", total)])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExportFormat;
+    use super::LevelStats;
+
+    fn run() -> Vec<LevelStats> {
+        vec![
+            LevelStats {
+                level_number: 1,
+                time: 12345,
+                moves: 20,
+                deaths: 1,
+                restarts: 0,
+                pickups: 2,
+                ..LevelStats::default()
+            },
+            LevelStats {
+                level_number: 2,
+                time: 1000,
+                moves: 5,
+                deaths: 0,
+                restarts: 1,
+                pickups: 0,
+                hints: 3,
+                ..LevelStats::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn score() {
+        let stats = run();
+        // 1000 + 100 - 12 - 20 - 100
+        assert_eq!(stats[0].score(), 968);
+        // 1000 - 1 - 5 - 50
+        assert_eq!(stats[1].score(), 944);
+        let slow = LevelStats {
+            time: 2_000_000,
+            ..LevelStats::new(1)
+        };
+        assert_eq!(slow.score(), 0);
+        let unfinished = LevelStats {
+            deaths: 2,
+            unfinished: true,
+            ..LevelStats::new(1)
+        };
+        assert_eq!(unfinished.score(), 0);
+    }
+
+    #[test]
+    fn score_with_par() {
+        let stats = LevelStats {
+            time: 10_000,
+            moves: 10,
+            par_time: Some(10_000),
+            par_moves: Some(9),
+            ..LevelStats::new(1)
+        };
+        // 1000 + 200 - 10 - 10
+        assert_eq!(stats.score(), 1180);
+        let stats = LevelStats {
+            par_moves: Some(10),
+            ..stats
+        };
+        assert_eq!(stats.score(), 1380);
+    }
+
+    #[test]
+    fn totals() {
+        let total = super::totals(&run());
+        assert_eq!(total.level_number, 0);
+        assert_eq!(total.time, 13345);
+        assert_eq!(total.moves, 25);
+        assert_eq!(total.deaths, 1);
+        assert_eq!(total.restarts, 1);
+        assert_eq!(total.pickups, 2);
+        assert_eq!(total.hints, 3);
+        assert_eq!(super::total_score(&run()), 1912);
+    }
+
+    #[test]
+    fn export_format() {
+        assert_eq!(
+            ExportFormat::from_path("results.csv"),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(
+            ExportFormat::from_path("out/results.json"),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(ExportFormat::from_path("results.txt"), None);
+    }
+
+    #[test]
+    fn to_csv() {
+        assert_eq!(
+            super::export(&run(), ExportFormat::Csv),
+            "level,time_ms,time,moves,deaths,restarts,pickups,hints,score,finished\n\
+             1,12345,00:12.345,20,1,0,2,0,968,true\n\
+             2,1000,00:01.000,5,0,1,0,3,944,true\n\
+             total,13345,00:13.345,25,1,1,2,3,1912,true\n"
+        );
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(
+            super::export(&run()[1..], ExportFormat::Json),
+            "{\"levels\": [{\"level\": 2, \"time_ms\": 1000, \"time\": \"00:01.000\", \
+             \"moves\": 5, \"deaths\": 0, \"restarts\": 1, \"pickups\": 0, \"hints\": 3, \"score\": 944, \"finished\": true}], \
+             \"total\": {\"time_ms\": 1000, \"time\": \"00:01.000\", \"moves\": 5, \
+             \"deaths\": 0, \"restarts\": 1, \"pickups\": 0, \"hints\": 3, \"score\": 944, \
+             \"finished\": true}}\n"
+        );
+        assert!(super::to_json(&[]).starts_with("{\"levels\": [], \"total\""));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::utils::direction::Direction;
+use crate::utils::point::Point;
+
+/// Something that happened while playing a level, a trigger can wait for it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TriggerEvent {
+    /// the player moved onto the point, also by sliding, teleporting or on a conveyor
+    PlayerEntered(Point),
+    /// an enemy moved onto the point
+    EnemyEntered(Point),
+    /// the timer with the id started by `Action::StartTimer` ran out
+    TimerExpired(u8),
+}
+
+/// What a trigger does to the level when it fires.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    OpenDoors(u8),
+    CloseDoors(u8),
+    /// only when nothing else is on the point
+    SpawnEnemy(Point, Direction),
+    /// shown below the status bar until the next message
+    ShowMessage(String),
+    /// counts enemy ticks, `TriggerEvent::TimerExpired` follows after the given number of them
+    StartTimer {
+        id: u8,
+        ticks: u32,
+    },
+}
+
+/// Actions run when an event happens, part of the level data.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Trigger {
+    pub event: TriggerEvent,
+    pub actions: Vec<Action>,
+    /// fire only the first time the event happens
+    pub once: bool,
+}
+
+impl Trigger {
+    pub fn new(event: TriggerEvent, actions: Vec<Action>) -> Self {
+        Trigger {
+            event,
+            actions,
+            once: true,
+        }
+    }
+
+    pub fn repeating(event: TriggerEvent, actions: Vec<Action>) -> Self {
+        Trigger {
+            once: false,
+            ..Trigger::new(event, actions)
+        }
+    }
+}
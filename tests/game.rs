@@ -0,0 +1,473 @@
+// Plays the game end to end with scripted input, drawing into a buffer instead of the terminal.
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyModifiers;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
+use didu::game::game_loop;
+use didu::game::menu_loop;
+use didu::game::play;
+use didu::levels::all;
+use didu::utils::clock::Clock;
+use didu::utils::clock::ManualClock;
+use didu::utils::config::Config;
+use didu::utils::drawing::Drawing;
+use didu::utils::input::ScriptedInput;
+use didu::utils::progress::Progress;
+use didu::utils::solver::path_to;
+use didu::utils::solver::MAX_STATES;
+use didu::{Cell, Direction, Inventory, Level, Point, Powerup, Solution};
+use std::time::Duration;
+
+fn arrow_keys(moves: &[Direction]) -> Vec<KeyCode> {
+    moves
+        .iter()
+        .map(|direction| match direction {
+            Direction::Up => KeyCode::Up,
+            Direction::Down => KeyCode::Down,
+            Direction::Left => KeyCode::Left,
+            Direction::Right => KeyCode::Right,
+        })
+        .collect()
+}
+
+// The keys of a shortest way through the level, after skipping its intro.
+// None when there is no way that works without waiting for the enemies to move.
+fn solution_keys(level: &Level) -> Option<Vec<KeyCode>> {
+    let mut keys = vec![];
+    if !level.info().intro.is_empty() {
+        keys.push(KeyCode::Enter);
+    }
+    // without idle polls no time passes and the enemies stand still, so the way has to go around them
+    let exit = level.finish_position()?;
+    match path_to(level, Inventory::new(), exit, MAX_STATES) {
+        Solution::Path(moves) => keys.extend(arrow_keys(&moves)),
+        _ => return None,
+    }
+    Some(keys)
+}
+
+// @.X
+fn corridor() -> Level {
+    let mut level = Level::empty(3, 1);
+    level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    level.update(Point { x: 2, y: 0 }, Cell::Exit);
+    level
+}
+
+// Time only passes with the idle polls of the script.
+fn run(
+    levels: Vec<Level>,
+    input: &mut ScriptedInput,
+    clock: &ManualClock,
+    config: &Config,
+) -> (Vec<didu::utils::stats::LevelStats>, Progress, String) {
+    let mut output: Vec<u8> = vec![];
+    let mut progress = Progress::new();
+    let stats = game_loop(
+        &mut Drawing::new(&mut output),
+        input,
+        clock,
+        levels,
+        0,
+        &mut progress,
+        config,
+    )
+    .unwrap();
+    (
+        stats,
+        progress,
+        String::from_utf8_lossy(&output).into_owned(),
+    )
+}
+
+#[test]
+fn plays_all_levels() {
+    let levels = all::levels();
+    // time passes with the idle polls, 100ms each, so the enemies can move between the keys
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new().with_clock(clock.clone());
+    for level in &levels {
+        input = match level.info().name.as_str() {
+            // wait for the guards to turn away, after one, two and five enemy moves
+            "Guards" => input
+                .key(KeyCode::Enter)
+                .keys([KeyCode::Right, KeyCode::Right, KeyCode::Down])
+                .idles(5)
+                .key(KeyCode::Right)
+                .idles(5)
+                .key(KeyCode::Right)
+                .idles(15)
+                .keys([KeyCode::Right, KeyCode::Up, KeyCode::Right, KeyCode::Right]),
+            // see waiting_for_the_enemy_on_the_plate
+            "Under Pressure" => input
+                .key(KeyCode::Enter)
+                .keys([KeyCode::Right; 8])
+                .idles(15)
+                .keys([KeyCode::Down; 4])
+                .key(KeyCode::Right),
+            name => input.keys(solution_keys(level).unwrap_or_else(|| panic!("{}", name))),
+        };
+    }
+    let (stats, progress, output) = run(levels.clone(), &mut input, &clock, &Config::new());
+    assert_eq!(stats.len(), levels.len());
+    for (level_index, (level_stats, level)) in stats.iter().zip(&levels).enumerate() {
+        let name = &level.info().name;
+        assert_eq!(level_stats.level_number, level_index + 1, "{}", name);
+        assert!(!level_stats.unfinished, "{}", name);
+        assert_eq!(level_stats.deaths, 0, "{}", name);
+        assert_eq!(level_stats.restarts, 0);
+        assert!(progress.best_time(level_index).is_some(), "{}", name);
+    }
+    assert_eq!(stats[0].moves, 6);
+    // every finished level unlocks the next one, after the last there is none left
+    assert_eq!(progress.unlocked(), levels.len() + 1);
+    assert!(output.contains("First Steps"));
+    assert_eq!(input.remaining(), 0);
+}
+
+#[test]
+fn quitting_ends_the_run() {
+    let mut input = ScriptedInput::new().keys([KeyCode::Right, KeyCode::Char('q')]);
+    let (stats, progress, _) = run(
+        vec![corridor(), corridor()],
+        &mut input,
+        &ManualClock::new(),
+        &Config::new(),
+    );
+    // the level quit on is kept, without moves
+    assert_eq!(stats.len(), 1);
+    assert!(stats[0].unfinished);
+    assert_eq!(stats[0].moves, 0);
+    assert_eq!(progress.unlocked(), 1);
+    assert_eq!(input.remaining(), 0);
+}
+
+#[test]
+fn quitting_the_intro() {
+    let mut level = corridor();
+    level.set_info(didu::LevelInfo {
+        intro: String::from("Walk right."),
+        ..didu::LevelInfo::new("Corridor")
+    });
+    let mut input = ScriptedInput::new().key(KeyCode::Esc);
+    let (stats, _, output) = run(vec![level], &mut input, &ManualClock::new(), &Config::new());
+    assert!(stats.is_empty());
+    assert!(output.contains("Walk right."));
+}
+
+#[test]
+fn restarting_a_level() {
+    let mut input = ScriptedInput::new()
+        .keys([KeyCode::Right, KeyCode::Char('r')])
+        .keys([KeyCode::Right, KeyCode::Right]);
+    let (stats, _, _) = run(
+        vec![corridor()],
+        &mut input,
+        &ManualClock::new(),
+        &Config::new(),
+    );
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].restarts, 1);
+    // only the moves after the restart count
+    assert_eq!(stats[0].moves, 2);
+}
+
+#[test]
+fn bumping_into_the_border() {
+    let mut input =
+        ScriptedInput::new().keys([KeyCode::Left, KeyCode::Up, KeyCode::Right, KeyCode::Right]);
+    let (stats, _, _) = run(
+        vec![corridor()],
+        &mut input,
+        &ManualClock::new(),
+        &Config::new(),
+    );
+    assert_eq!(stats[0].moves, 2);
+}
+
+#[test]
+fn pausing_ignores_moves() {
+    let mut input = ScriptedInput::new()
+        .keys([KeyCode::Char('p'), KeyCode::Right, KeyCode::Right])
+        .idle()
+        .keys([KeyCode::Char('p'), KeyCode::Right, KeyCode::Right]);
+    let (stats, _, output) = run(
+        vec![corridor()],
+        &mut input,
+        &ManualClock::new(),
+        &Config::new(),
+    );
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].moves, 2);
+    assert!(output.contains("PAUSED"));
+}
+
+#[test]
+fn dying_with_the_last_life() {
+    // @ X
+    let mut level = corridor();
+    level.update(Point { x: 1, y: 0 }, Cell::Void);
+    let mut input = ScriptedInput::new().key(KeyCode::Right);
+    let (stats, _, output) = run(
+        vec![level, corridor()],
+        &mut input,
+        &ManualClock::new(),
+        &Config {
+            lives: Some(1),
+            ..Config::new()
+        },
+    );
+    assert_eq!(stats.len(), 1);
+    assert!(stats[0].unfinished);
+    assert_eq!(stats[0].deaths, 1);
+    assert!(output.contains("Game over"));
+}
+
+#[test]
+fn dying_in_casual_mode() {
+    // @ X with a way around
+    // ...
+    let mut level = Level::empty(3, 2);
+    level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    level.update(Point { x: 1, y: 0 }, Cell::Void);
+    level.update(Point { x: 2, y: 0 }, Cell::Exit);
+    let mut input = ScriptedInput::new().key(KeyCode::Right).keys([
+        KeyCode::Down,
+        KeyCode::Right,
+        KeyCode::Right,
+        KeyCode::Up,
+    ]);
+    let (stats, _, _) = run(vec![level], &mut input, &ManualClock::new(), &Config::new());
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].deaths, 1);
+    assert_eq!(stats[0].moves, 4);
+}
+
+#[test]
+fn results_after_a_run() {
+    let mut output: Vec<u8> = vec![];
+    let mut input = ScriptedInput::new()
+        .keys([KeyCode::Right, KeyCode::Right])
+        // any key goes back to the menu
+        .key(KeyCode::Enter);
+    let stats = play(
+        &mut Drawing::new(&mut output),
+        &mut input,
+        &ManualClock::new(),
+        &Config::new(),
+        vec![corridor()],
+        0,
+        &mut Progress::new(),
+    )
+    .unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(input.remaining(), 0);
+    assert!(String::from_utf8_lossy(&output).contains("Level | Time"));
+}
+
+#[test]
+fn narrating_the_menu() {
+    let mut output: Vec<u8> = vec![];
+    let config = Config {
+        accessible: true,
+        ..Config::new()
+    };
+    let input = ScriptedInput::new().keys([KeyCode::Down, KeyCode::Char('q')]);
+    menu_loop(
+        Drawing::new(&mut output),
+        input,
+        ManualClock::new(),
+        config,
+        vec![corridor()],
+        Progress::new(),
+    )
+    .unwrap();
+    let output = String::from_utf8_lossy(&output);
+    // the whole screen first, then only the entry moved to
+    assert!(output.contains("Diamand Duo\r\nPlay\r\nContinue\r\n"));
+    assert!(output.contains("Selected: Play\r\n"));
+    assert!(output.contains("quit\r\nContinue\r\n"));
+    assert!(!output.contains("> Play"));
+}
+
+#[test]
+fn narrating_the_intro_and_results() {
+    let mut output: Vec<u8> = vec![];
+    let mut level = corridor();
+    level.set_info(didu::LevelInfo {
+        intro: String::from("Walk right."),
+        ..didu::LevelInfo::new("Corridor")
+    });
+    let mut input = ScriptedInput::new()
+        .keys([KeyCode::Enter, KeyCode::Right, KeyCode::Right])
+        .key(KeyCode::Enter);
+    let config = Config {
+        accessible: true,
+        ..Config::new()
+    };
+    play(
+        &mut Drawing::new(&mut output),
+        &mut input,
+        &ManualClock::new(),
+        &config,
+        vec![level],
+        0,
+        &mut Progress::new(),
+    )
+    .unwrap();
+    let output = String::from_utf8_lossy(&output);
+    assert!(output.contains("Level 1: Corridor\r\nWalk right.\r\n"));
+    assert!(output.contains("Level 1: time 00:00.000, 2 moves"));
+    assert!(output.contains("\r\nPress any key to continue\r\n"));
+    assert!(!output.contains("Level | Time"));
+}
+
+#[test]
+fn script_running_out() {
+    let mut output: Vec<u8> = vec![];
+    let mut input = ScriptedInput::new().key(KeyCode::Right);
+    let result = game_loop(
+        &mut Drawing::new(&mut output),
+        &mut input,
+        &ManualClock::new(),
+        vec![corridor()],
+        0,
+        &mut Progress::new(),
+        &Config::new(),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn completion_time() {
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .key(KeyCode::Right)
+        .idles(3)
+        .key(KeyCode::Right);
+    let (stats, progress, _) = run(vec![corridor()], &mut input, &clock, &Config::new());
+    assert_eq!(stats[0].time, 300);
+    assert_eq!(progress.best_time(0), Some(300));
+}
+
+#[test]
+fn pausing_stops_the_timer() {
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .idles(2)
+        .keys([KeyCode::Right, KeyCode::Char('p')])
+        .idles(10)
+        .keys([KeyCode::Char('p'), KeyCode::Right]);
+    let (stats, _, _) = run(vec![corridor()], &mut input, &clock, &Config::new());
+    assert_eq!(stats[0].time, 200);
+    assert_eq!(clock.now(), Duration::from_millis(1200));
+}
+
+// @.E
+// X..
+fn guarded() -> Level {
+    let mut level = Level::empty(3, 2);
+    level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    level.update(
+        Point { x: 2, y: 0 },
+        Cell::CounterClockwiseEnemy(Direction::Left),
+    );
+    level.update(Point { x: 0, y: 1 }, Cell::Exit);
+    level
+}
+
+#[test]
+fn enemies_move_on_time() {
+    // one step after 500ms, the second one catches the player
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .idles(9)
+        .key(KeyCode::Down);
+    let (stats, _, _) = run(vec![guarded()], &mut input, &clock, &Config::new());
+    assert_eq!(stats[0].deaths, 0);
+    assert_eq!(stats[0].time, 900);
+
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .idles(10)
+        .key(KeyCode::Down);
+    let (stats, _, _) = run(vec![guarded()], &mut input, &clock, &Config::new());
+    assert_eq!(stats[0].deaths, 1);
+    // the timer starts over with the level
+    assert_eq!(stats[0].time, 0);
+}
+
+#[test]
+fn walking_around_an_enemy_in_the_way() {
+    // @........X
+    // .......^..
+    let mut level = Level::empty(10, 2);
+    level.update(Point { x: 0, y: 0 }, Cell::Player(Powerup::None));
+    level.update(Point { x: 9, y: 0 }, Cell::Exit);
+    level.update(
+        Point { x: 7, y: 1 },
+        Cell::CounterClockwiseEnemy(Direction::Up),
+    );
+    let click = Event::Mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 9,
+        row: 0,
+        modifiers: KeyModifiers::NONE,
+    });
+    // one move every poll, the enemy steps into the way after 500ms
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .event(click)
+        .idles(20);
+    let (stats, _, _) = run(vec![level], &mut input, &clock, &Config::new());
+    assert_eq!(stats.len(), 1);
+    assert!(!stats[0].unfinished);
+    assert_eq!(stats[0].deaths, 0);
+    // around the enemy below it
+    assert_eq!(stats[0].moves, 11);
+}
+
+#[test]
+fn pausing_stops_the_enemies() {
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .idles(9)
+        .key(KeyCode::Char('p'))
+        .idles(20)
+        .key(KeyCode::Char('p'))
+        .key(KeyCode::Down);
+    let (stats, _, _) = run(vec![guarded()], &mut input, &clock, &Config::new());
+    assert_eq!(stats[0].deaths, 0);
+    assert_eq!(stats[0].time, 900);
+}
+
+#[test]
+fn waiting_for_the_enemy_on_the_plate() {
+    let level = all::levels()
+        .into_iter()
+        .find(|level| level.info().name == "Under Pressure")
+        .unwrap();
+    // three enemy moves, 500ms each, take it onto the pressure plate below the door
+    let clock = ManualClock::new();
+    let mut input = ScriptedInput::new()
+        .with_clock(clock.clone())
+        .key(KeyCode::Enter)
+        .keys([KeyCode::Right; 8])
+        .idles(15)
+        .keys([KeyCode::Down; 4])
+        .key(KeyCode::Right);
+    let (stats, _, _) = run(vec![level], &mut input, &clock, &Config::new());
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].deaths, 0);
+    assert_eq!(stats[0].time, 1500);
+    assert_eq!(Some(stats[0].moves), stats[0].par_moves);
+    assert_eq!(input.remaining(), 0);
+}